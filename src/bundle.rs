@@ -0,0 +1,96 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+
+use crate::{
+    commented_toml, compare_names, load_config, save_config, sort_config, split_remote, Projects,
+};
+
+/// write the config to `file` with absolute paths and the projects sorted by name, so the same
+/// config always gives the same file
+pub fn export(config: &Projects, file: &Path, force: bool) -> Result<()> {
+    if file.try_exists()? && !force {
+        anyhow::bail!(
+            "'{}' already exists, use --force to overwrite it",
+            file.display()
+        );
+    }
+    let mut bundle = config.clone();
+    for project in bundle.paths.values_mut() {
+        project.path = absolute(&project.path)?;
+        for path in &mut project.more_paths {
+            *path = absolute(path)?;
+        }
+    }
+    for dir in bundle.dirs.iter_mut().flatten() {
+        dir.path = absolute(&dir.path)?;
+    }
+    for dir in bundle.template_dirs.iter_mut().flatten() {
+        *dir = absolute(dir)?;
+    }
+    bundle
+        .paths
+        .sort_by(|a, _, b, _| compare_names(config, a, b));
+    fs::write(file, commented_toml(&bundle)?)?;
+    println!(
+        "exported {} projects to '{}'",
+        bundle.paths.len(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// relative paths are relative to the current directory, remote paths and paths starting with `~`
+/// or a variable are kept as they are
+fn absolute(path: &str) -> Result<String> {
+    // `~` and variables are expanded on the machine the config is used on
+    if split_remote(path).is_some() || path.starts_with(['~', '$']) {
+        return Ok(path.to_string());
+    }
+    Ok(std::path::absolute(path)?.to_string_lossy().to_string())
+}
+
+/// add the projects, dirs, source commands and templates of an exported config that are missing.
+/// Settings like open_cmd stay as they are, projects with a name that is already taken are skipped
+pub fn import(config: &mut Projects, config_file: &Path, file: &Path, save: bool) -> Result<()> {
+    let bundle = load_config(file)?;
+    let mut projects = 0;
+    for (name, project) in bundle.paths {
+        match config.paths.get(&name) {
+            Some(existing) if existing.path != project.path => eprintln!(
+                "skipping '{name}': already configured with path '{}'",
+                existing.path
+            ),
+            Some(_) => (),
+            None => {
+                config.paths.insert(name, project);
+                projects += 1;
+            }
+        }
+    }
+    let mut dirs = 0;
+    let configured = config.dirs.get_or_insert_with(Vec::new);
+    for dir in bundle.dirs.into_iter().flatten() {
+        if !configured.iter().any(|d| d.path == dir.path) {
+            configured.push(dir);
+            dirs += 1;
+        }
+    }
+    for (configured, imported) in [
+        (&mut config.source_cmds, bundle.source_cmds),
+        (&mut config.template_dirs, bundle.template_dirs),
+    ] {
+        for entry in imported.into_iter().flatten() {
+            let configured = configured.get_or_insert_with(Vec::new);
+            if !configured.contains(&entry) {
+                configured.push(entry);
+            }
+        }
+    }
+    sort_config(config);
+    if save {
+        save_config(config, &config_file.to_path_buf())?;
+    }
+    println!("imported {projects} projects and {dirs} dirs");
+    Ok(())
+}
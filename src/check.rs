@@ -0,0 +1,82 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+
+use crate::{
+    alias_conflicts,
+    error::{CodedError, ErrorCode},
+    expand::expand_path,
+    load_config, reserved_conflicts, split_remote,
+};
+
+/// validate the config without asking or opening anything. Every problem is printed with its
+/// key and path, missing paths and dirs are an error, unreadable dirs and name conflicts only a
+/// warning unless `strict` is set
+pub fn check(config_file: &Path, strict: bool) -> Result<()> {
+    if !config_file.try_exists()? {
+        return Err(CodedError::new(
+            ErrorCode::PathMissing,
+            format!("config file '{}' does not exist", config_file.display()),
+        )
+        .into());
+    }
+    let config = load_config(config_file)?;
+    let mut problems = vec![];
+    let mut warnings = vec![];
+    for (name, project) in &config.paths {
+        for path in [&project.path].into_iter().chain(&project.more_paths) {
+            // remote paths can't be checked
+            if split_remote(path).is_some() {
+                continue;
+            }
+            match expand_path(path).try_exists() {
+                Ok(true) => (),
+                Ok(false) => problems.push(format!("paths.{name}: '{path}' does not exist")),
+                Err(err) => warnings.push(format!("paths.{name}: can't check '{path}': {err}")),
+            }
+        }
+    }
+    for (i, dir) in config.dirs.iter().flatten().enumerate() {
+        let path = expand_path(&dir.path);
+        match fs::metadata(&path) {
+            Ok(metadata) if !metadata.is_dir() => {
+                problems.push(format!("dirs[{i}]: '{}' is not a directory", dir.path))
+            }
+            Ok(_) => {
+                if let Err(err) = fs::read_dir(&path) {
+                    warnings.push(format!("dirs[{i}]: can't read '{}': {err}", dir.path));
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                problems.push(format!("dirs[{i}]: '{}' does not exist", dir.path))
+            }
+            Err(err) => warnings.push(format!("dirs[{i}]: can't check '{}': {err}", dir.path)),
+        }
+    }
+    let conflicts = alias_conflicts(&config, None)
+        .into_iter()
+        .chain(reserved_conflicts(&config, None));
+    match strict {
+        true => problems.extend(conflicts),
+        false => warnings.extend(conflicts),
+    }
+    for warning in &warnings {
+        println!("warning: {warning}");
+    }
+    for problem in &problems {
+        println!("error: {problem}");
+    }
+    if !problems.is_empty() {
+        return Err(CodedError::new(
+            ErrorCode::ConfigInvalid,
+            format!(
+                "found {} problems in '{}'",
+                problems.len(),
+                config_file.display()
+            ),
+        )
+        .into());
+    }
+    println!("config file '{}' is valid", config_file.display());
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::{fmt::Display, fs, path::Path, process::ExitStatus, time::SystemTime};
+
+/// stable error codes printed in `--porcelain` mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// the config file could not be parsed or contains invalid values
+    ConfigInvalid,
+    /// a configured or given path does not exist
+    PathMissing,
+    /// the open command or editor could not be found
+    CmdNotFound,
+    /// the open command exited with a non-zero status
+    CmdFailed,
+    /// reading or writing a file failed
+    Io,
+    /// any other error
+    Other,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ConfigInvalid => "config_invalid",
+            ErrorCode::PathMissing => "path_missing",
+            ErrorCode::CmdNotFound => "cmd_not_found",
+            ErrorCode::CmdFailed => "cmd_failed",
+            ErrorCode::Io => "io",
+            ErrorCode::Other => "other",
+        }
+    }
+
+    /// find the code for an error, explicitly tagged errors take precedence
+    pub fn of(err: &anyhow::Error) -> Self {
+        if let Some(err) = err.chain().find_map(|e| e.downcast_ref::<CodedError>()) {
+            return err.code;
+        }
+        if err.chain().any(|e| e.is::<OpenCmdNotFound>()) {
+            return ErrorCode::CmdNotFound;
+        }
+        if err.chain().any(|e| e.is::<CommandFailed>()) {
+            return ErrorCode::CmdFailed;
+        }
+        if err.chain().any(|e| e.is::<toml::de::Error>()) {
+            return ErrorCode::ConfigInvalid;
+        }
+        if err.chain().any(|e| e.is::<std::io::Error>()) {
+            return ErrorCode::Io;
+        }
+        ErrorCode::Other
+    }
+}
+
+/// error with an explicit code for `--porcelain` output
+#[derive(Debug)]
+pub struct CodedError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CodedError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for CodedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+/// command that exited with a non-zero status, wspick exits with the same code
+#[derive(Debug)]
+pub struct CommandFailed {
+    pub cmd: String,
+    pub status: ExitStatus,
+}
+
+impl CommandFailed {
+    /// exit code of the command, 1 if it was killed by a signal
+    pub fn exit_code(&self) -> i32 {
+        self.status.code().unwrap_or(1)
+    }
+}
+
+impl Display for CommandFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' failed ({})", self.cmd, self.status)
+    }
+}
+
+impl std::error::Error for CommandFailed {}
+
+/// the program of open_cmd could not be started, the menu offers to pick another one
+#[derive(Debug)]
+pub struct OpenCmdNotFound(pub String);
+
+impl Display for OpenCmdNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "open_cmd '{}' not found", self.0)
+    }
+}
+
+impl std::error::Error for OpenCmdNotFound {}
+
+/// exit code for an error, the code of a failed command or 1
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<CommandFailed>())
+        .map_or(1, CommandFailed::exit_code)
+}
+
+/// print an error as a single `<code>: <message>` line
+pub fn print_porcelain(err: &anyhow::Error) {
+    let message = format!("{err:#}").replace(['\n', '\r'], " ");
+    eprintln!("{}: {message}", ErrorCode::of(err).as_str());
+}
+
+/// write the error with a timestamp to `file`, failures are ignored to not hide the original error
+pub fn record(err: &anyhow::Error, file: &Path) {
+    if let Some(parent) = file.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let time = crate::format_timestamp(SystemTime::now());
+    let _ = fs::write(file, format!("{time}\n{err:?}\n"));
+}
@@ -0,0 +1,173 @@
+use crate::Projects;
+
+/// config with every option documented, optional ones commented out with an example value
+pub fn example_config(editor: &str) -> String {
+    let docs = Projects::get_docs();
+    let editor_value = toml::Value::from(editor);
+    format!(
+        r#"# {dirs}
+# all subdirectories are shown as projects
+dirs = [
+    # "/home/me/code",
+    # the directory itself is a project
+    # {{ path = "/home/me/notes", depth = 0 }},
+    # shown after the other projects, newest first
+    # {{ path = "/home/me/scratch", sort = "mtime" }},
+]
+
+# {source_cmds}
+# source_cmds = ["list-remote-projects"]
+
+# {open_cmd}
+# the path is passed as last argument, e.g. "code", "idea" or "nautilus",
+# or where a {{workspace}} placeholder is, e.g. "code --new-window {{workspace}}"
+# or its short form {{}}, e.g. "sh -c 'cd {{}} && nvim'"
+open_cmd = ""
+
+# {open_rules}
+# checked in order, projects matching no rule use open_cmd
+# open_rules = [
+#     {{ marker = "Cargo.toml", cmd = "rustrover" }},
+#     {{ marker = "package.json", cmd = "code" }},
+# ]
+
+# {multiplexer}
+# attaches to a session named after the project or creates it in the project directory
+# multiplexer = "tmux"
+
+# {reuse_window}
+reuse_window = false
+
+# {stdin_path}
+stdin_path = false
+
+# {confirm_open}
+confirm_open = false
+
+# {pre_open_cmd}
+# pre_open_cmd = "devcontainer-up"
+
+# {pre_open_abort}
+pre_open_abort = true
+
+# {init_cmd}
+# runs with the project as working directory, {{name}} and {{path}} are replaced
+# init_cmd = "git fetch"
+
+# {init_cmd_abort}
+init_cmd_abort = false
+
+# {editor}
+# arguments are possible, e.g. "code --wait"
+editor = {editor_value}
+
+# {template_dirs}
+# template_dirs = ["/home/me/templates/rust"]
+
+# {sort}
+sort = true
+
+# {sort_case_sensitive}
+sort_case_sensitive = false
+
+# {menu_sort}
+# "recent" shows the last opened projects first
+# menu_sort = "recent"
+
+# {exclude_proj_dirs}
+exclude_proj_dirs = false
+
+# {scan_timeout_ms}
+# scan_timeout_ms = 2000
+
+# {normalize_names}
+# e.g. "My_App" is shown as "my-app"
+normalize_names = false
+
+# {resolve_git_root}
+resolve_git_root = false
+
+# {loop_menu}
+loop_menu = false
+
+# {esc_exits}
+esc_exits = false
+
+# {log_open_output}
+log_open_output = false
+
+# {log_sessions}
+log_sessions = false
+
+# {show_paths}
+show_paths = false
+
+# {hide_missing}
+hide_missing = false
+
+# {detect_lang}
+detect_lang = false
+
+# {show_git}
+show_git = false
+
+# {theme}
+[theme]
+# preset = "boxed"
+# selected_prefix = "->"
+# highlight_color = "cyan"
+# help_message = true
+
+# {paths}
+[paths]
+# a project is either just a path
+# notes = "/home/me/notes"
+# or a list of folders opened together
+# app = ["/home/me/code/app", "/home/me/code/app-docs"]
+# or a table with additional settings
+# [paths.website]
+# path = "/home/me/code/website"
+# aliases = ["web"]
+# tags = ["work"]
+# pre_open_cmd = "docker-compose-up"
+# init_cmd = "docker compose up -d"
+# reuse_window = true
+# open_cmd = "code --new-window"
+# workspace_file = "website.code-workspace"
+# lang = "typescript"
+# recent_file = true
+# elevate = false
+"#,
+        dirs = docs.dirs,
+        source_cmds = docs.source_cmds,
+        open_cmd = docs.open_cmd,
+        open_rules = docs.open_rules,
+        multiplexer = docs.multiplexer,
+        reuse_window = docs.reuse_window,
+        stdin_path = docs.stdin_path,
+        confirm_open = docs.confirm_open,
+        pre_open_cmd = docs.pre_open_cmd,
+        pre_open_abort = docs.pre_open_abort,
+        init_cmd = docs.init_cmd,
+        init_cmd_abort = docs.init_cmd_abort,
+        editor = docs.editor,
+        template_dirs = docs.template_dirs,
+        sort = docs.sort,
+        sort_case_sensitive = docs.sort_case_sensitive,
+        menu_sort = docs.menu_sort,
+        exclude_proj_dirs = docs.exclude_proj_dirs,
+        scan_timeout_ms = docs.scan_timeout_ms,
+        normalize_names = docs.normalize_names,
+        resolve_git_root = docs.resolve_git_root,
+        loop_menu = docs.loop_menu,
+        esc_exits = docs.esc_exits,
+        log_open_output = docs.log_open_output,
+        log_sessions = docs.log_sessions,
+        show_paths = docs.show_paths,
+        hide_missing = docs.hide_missing,
+        detect_lang = docs.detect_lang,
+        show_git = docs.show_git,
+        theme = docs.theme,
+        paths = docs.paths,
+    )
+}
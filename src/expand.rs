@@ -0,0 +1,116 @@
+use std::{env, path::PathBuf};
+
+use crate::split_remote;
+
+/// configured path with a leading `~` or `~user` and `$VAR` or `${VAR}` expanded. Undefined
+/// variables and unknown users are kept as they are, remote paths are expanded on the host
+pub fn expand_path(path: &str) -> PathBuf {
+    if split_remote(path).is_some() {
+        return PathBuf::from(path);
+    }
+    expand_tilde(&expand_vars(path))
+}
+
+/// same as [`expand_path`] for places that pass paths on as strings
+pub fn expand(path: &str) -> String {
+    expand_path(path).to_string_lossy().to_string()
+}
+
+fn expand_vars(path: &str) -> String {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let (name, len) = match rest.strip_prefix("${") {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 3),
+                None => ("", 0),
+            },
+            None => {
+                let name = &rest[1..];
+                let end = name
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(name.len());
+                (&name[..end], end + 1)
+            }
+        };
+        match env::var(name) {
+            Ok(value) if !name.is_empty() => {
+                expanded.push_str(&value);
+                rest = &rest[len..];
+            }
+            _ => {
+                expanded.push('$');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn expand_tilde(path: &str) -> PathBuf {
+    let Some(rest) = path.strip_prefix('~') else {
+        return PathBuf::from(path);
+    };
+    let end = rest.find(['/', '\\']).unwrap_or(rest.len());
+    let (user, rest) = rest.split_at(end);
+    let home = if user.is_empty() {
+        directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+    } else {
+        user_home(user)
+    };
+    match home {
+        // `join` would replace the home with an absolute rest
+        Some(home) => PathBuf::from(format!("{}{rest}", home.display())),
+        None => PathBuf::from(path),
+    }
+}
+
+/// home directory of another user from `/etc/passwd`
+#[cfg(unix)]
+fn user_home(user: &str) -> Option<PathBuf> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        (fields.first() == Some(&user))
+            .then(|| fields.get(5).map(PathBuf::from))
+            .flatten()
+    })
+}
+
+/// other users are only supported on unix
+#[cfg(not(unix))]
+fn user_home(_: &str) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tilde_is_home() {
+        let home = directories::BaseDirs::new()
+            .unwrap()
+            .home_dir()
+            .to_path_buf();
+        assert_eq!(expand_path("~"), home);
+        assert_eq!(expand_path("~/code"), home.join("code"));
+    }
+
+    #[test]
+    fn undefined_variable_is_kept() {
+        assert_eq!(
+            expand("$WSPICK_UNDEFINED_VAR/code"),
+            "$WSPICK_UNDEFINED_VAR/code"
+        );
+        assert_eq!(expand("${WSPICK_UNDEFINED_VAR}"), "${WSPICK_UNDEFINED_VAR}");
+    }
+
+    #[test]
+    fn absolute_path_is_unchanged() {
+        assert_eq!(expand("/home/me/code"), "/home/me/code");
+    }
+}
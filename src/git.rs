@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::Ordering,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{expand::expand_path, program, split_remote, NO_EXEC};
+
+/// time `git status` may take per repository before the dirty marker is left out
+const STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// `(branch)` or `(branch*)` with uncommitted changes for every project that is a git
+/// repository, checked in parallel. Other projects and failures get no entry
+pub fn annotations(projects: &[(String, String)]) -> HashMap<String, String> {
+    thread::scope(|scope| {
+        let handles = projects
+            .iter()
+            .map(|(name, path)| (name, scope.spawn(|| annotation(path))))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .filter_map(|(name, handle)| Some((name.clone(), handle.join().ok()??)))
+            .collect()
+    })
+}
+
+fn annotation(path: &str) -> Option<String> {
+    if split_remote(path).is_some() {
+        return None;
+    }
+    let dir = expand_path(path);
+    let branch = branch(&git_dir(&dir)?)?;
+    let dirty = if dirty(&dir) == Some(true) { "*" } else { "" };
+    Some(format!("({branch}{dirty})"))
+}
+
+/// the `.git` directory of a repository, worktrees and submodules point to it with a `.git` file
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+    let git = dir.join(".git");
+    if git.is_dir() {
+        return Some(git);
+    }
+    let content = fs::read_to_string(&git).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(dir.join(target))
+}
+
+/// the checked out branch, the short commit hash for a detached HEAD
+fn branch(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        ),
+        None => Some(head.get(..7)?.to_string()),
+    }
+}
+
+/// whether the worktree has changes, `None` if git failed or took too long
+fn dirty(dir: &Path) -> Option<bool> {
+    // --no-exec starts no processes at all
+    if NO_EXEC.load(Ordering::Relaxed) {
+        return None;
+    }
+    let mut child = Command::new(program::resolve("git"))
+        .arg("-C")
+        .arg(dir)
+        .args(["status", "--porcelain"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // read while git runs, a full pipe would block it
+    let mut stdout = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut output = vec![];
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let deadline = Instant::now() + STATUS_TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait().ok()? {
+            break status;
+        }
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    let output = reader.join().ok()?.ok()?;
+    status.success().then_some(!output.is_empty())
+}
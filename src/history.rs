@@ -0,0 +1,121 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+
+/// the history file of this run, set once at startup
+static FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// use the history file next to `config_file`, `wspick.history.toml` for `wspick.toml`. Every
+/// config given with `--config` or `WSPICK_CONFIG` has its own history
+pub fn init(config_file: &Path) {
+    let _ = FILE.set(config_file.with_extension("history.toml"));
+}
+
+/// seconds since the epoch each path was last opened at. A missing or broken history is empty
+pub fn load() -> HashMap<String, u64> {
+    FILE.get()
+        .and_then(|file| fs::read_to_string(file).ok())
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// remember that `path` was opened now
+pub fn record(path: &str) -> Result<()> {
+    let Some(file) = FILE.get() else {
+        return Ok(());
+    };
+    let mut history = load();
+    history.insert(path.to_string(), now());
+    // written sorted so the file doesn't change order on every open
+    let sorted = history
+        .into_iter()
+        .collect::<std::collections::BTreeMap<_, _>>();
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, toml::to_string(&sorted)?)?;
+    Ok(())
+}
+
+/// paths opened within the last `duration`
+pub fn opened_since(duration: Duration) -> Vec<String> {
+    let since = now().saturating_sub(duration.as_secs());
+    load()
+        .into_iter()
+        .filter(|(_, opened)| *opened >= since)
+        .map(|(path, _)| path)
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// parse durations like `30m`, `12h`, `7d` or `2w`, plain numbers are seconds
+pub fn parse_duration(text: &str) -> std::result::Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{text}' doesn't start with a number"))?;
+    let factor = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("unknown unit '{unit}', use s, m, h, d or w")),
+    };
+    Ok(Duration::from_secs(number.saturating_mul(factor)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(text: &str) -> u64 {
+        parse_duration(text).unwrap().as_secs()
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(secs("30m"), 30 * 60);
+        assert_eq!(secs("12h"), 12 * 60 * 60);
+        assert_eq!(secs("7d"), 7 * 24 * 60 * 60);
+        assert_eq!(secs("1w"), 7 * 24 * 60 * 60);
+        assert_eq!(secs("0d"), 0);
+    }
+
+    #[test]
+    fn missing_unit_is_seconds() {
+        assert_eq!(secs("90"), 90);
+        assert_eq!(secs("90s"), 90);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7y").is_err());
+        assert!(parse_duration("7dd").is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        // too large for a number at all
+        assert!(parse_duration("99999999999999999999d").is_err());
+        // saturates instead of wrapping
+        assert_eq!(secs(&format!("{}w", u64::MAX / 2)), u64::MAX);
+    }
+}
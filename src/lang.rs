@@ -0,0 +1,48 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use crate::{expand::expand_path, project_settings, Projects};
+
+/// files that mark the main language of a project, checked in order
+const MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("package.json", "js"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("requirements.txt", "python"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.gradle.kts", "kotlin"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("pubspec.yaml", "dart"),
+    ("Package.swift", "swift"),
+    ("CMakeLists.txt", "c++"),
+];
+
+/// language of a project, the configured `lang` or detected by marker files
+pub fn project_lang(config: &Projects, name: &str, path: &str) -> Option<String> {
+    if let Some(lang) = project_settings(config, name, path).and_then(|p| p.lang.clone()) {
+        return Some(lang);
+    }
+    detect(path).map(String::from)
+}
+
+/// detected language of the directory, looked up once per run
+fn detect(path: &str) -> Option<&'static str> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<&'static str>>>> = OnceLock::new();
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    *cache.entry(path.to_string()).or_insert_with(|| {
+        MARKERS
+            .iter()
+            .find(|(file, _)| expand_path(path).join(file).exists())
+            .map(|(_, lang)| *lang)
+    })
+}
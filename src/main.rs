@@ -1,46 +1,362 @@
+mod bundle;
+mod check;
+mod error;
+mod example;
+mod expand;
+mod git;
+mod history;
+mod lang;
+mod manage;
+mod multiplexer;
+mod natural;
+mod palette;
+mod preview;
+mod program;
+mod recent;
+mod schema;
+#[cfg(unix)]
+mod serve;
+mod shell;
+mod source;
+mod template;
+#[cfg(test)]
+mod test_util;
+mod theme;
+
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Display,
     fs,
+    io::{IsTerminal, Write},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Args, Parser, ValueEnum};
+use crossterm::style::Stylize;
 use doc_consts::DocConsts;
+use error::{CodedError, CommandFailed, ErrorCode, OpenCmdNotFound};
+use expand::{expand, expand_path};
 use indexmap::IndexMap;
 use inquire::{
     validator::{ErrorMessage, StringValidator, Validation},
     CustomUserError,
 };
+use schemars::JsonSchema;
+use serde::{Deserializer, Serializer};
 use serde_derive::{Deserialize, Serialize};
+use theme::Theme;
 
-#[derive(Debug, Deserialize, Serialize, DocConsts)]
+#[derive(Debug, Clone, Deserialize, Serialize, DocConsts, JsonSchema)]
 struct Projects {
     /// Directories to search for projects
-    dirs: Option<Vec<String>>,
+    dirs: Option<Vec<SearchDir>>,
+    /// shell commands that print additional projects as `name<TAB>path` lines
+    source_cmds: Option<Vec<String>>,
     /// command to run with selected path as arg
     open_cmd: String,
+    /// commands to open projects containing a marker file with, the first matching rule is used
+    open_rules: Option<Vec<OpenRule>>,
+    /// open projects in a session of this terminal multiplexer instead, tmux or zellij
+    multiplexer: Option<String>,
+    /// open projects in an already open editor window if open_cmd supports it (code, nvim)
+    reuse_window: Option<bool>,
+    /// write the selected path to the stdin of open_cmd instead of passing it as arg
+    stdin_path: Option<bool>,
+    /// ask before running open_cmd, showing the complete command
+    confirm_open: Option<bool>,
+    /// command to run with selected path as arg before opening, e.g. to start a container
+    pre_open_cmd: Option<String>,
+    /// abort opening the project if pre_open_cmd fails
+    pre_open_abort: Option<bool>,
+    /// command to run inside the project directory after opening it, e.g. `git fetch`
+    init_cmd: Option<String>,
+    /// report opening the project as failed if init_cmd fails
+    init_cmd_abort: Option<bool>,
     /// editor to open config with
     editor: String,
+    /// directories that are copied to create a new project
+    template_dirs: Option<Vec<String>>,
     /// sort projects alphabetically
     sort: Option<bool>,
+    /// compare names case-sensitively when sorting, uppercase letters first
+    sort_case_sensitive: Option<bool>,
+    /// order of the menu if --sort is not given: name, mtime or recent
+    menu_sort: Option<MenuSort>,
     /// exclude directories that contain projects from automatic list
     exclude_proj_dirs: Option<bool>,
+    /// skip dirs and source_cmds that take longer to scan, e.g. hanging network mounts
+    scan_timeout_ms: Option<u64>,
+    /// show found projects lowercase with `-` instead of spaces and `_`, the paths stay as they are
+    normalize_names: Option<bool>,
+    /// open the root of the git repository containing the selected path instead of the path
+    resolve_git_root: Option<bool>,
+    /// return to the menu after opening a project instead of exiting
+    loop_menu: Option<bool>,
+    /// exit when Esc is pressed in a submenu instead of going back to the previous menu
+    esc_exits: Option<bool>,
+    /// write the output of open_cmd to a log file in the data directory
+    log_open_output: Option<bool>,
+    /// ask for a note when opening a project and log it to sessions.log
+    log_sessions: Option<bool>,
+    /// show the path of each project next to its name in the menu
+    show_paths: Option<bool>,
+    /// hide configured projects whose path doesn't exist from the menu
+    hide_missing: Option<bool>,
+    /// detect the main language of projects by marker files like Cargo.toml and show it in the menu
+    detect_lang: Option<bool>,
+    /// show the branch of git repositories in the menu, with `*` if they have uncommitted changes
+    show_git: Option<bool>,
+    /// colors and style of the menu
+    theme: Option<Theme>,
     /// Paths to specific projects
-    paths: IndexMap<String, String>,
+    paths: IndexMap<String, Project>,
+    /// keys of newer versions or added by the user, kept as they are when saving
+    #[serde(flatten)]
+    #[schemars(skip)]
+    extra: toml::Table,
+}
+
+/// command to open projects with that contain a marker file, e.g. `Cargo.toml`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+struct OpenRule {
+    /// file or directory that has to exist in the project
+    marker: String,
+    /// command used instead of open_cmd, with the same placeholders
+    cmd: String,
+}
+
+/// a directory to search for projects, stored either as a plain path or as a table with a depth
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(remote = "Self")]
+#[schemars(rename = "SearchDir", transform = schema::or_path)]
+struct SearchDir {
+    path: String,
+    /// level below the directory at which projects are, 0 makes the directory itself a project
+    #[serde(default = "default_depth")]
+    depth: usize,
+    /// order of the found projects, shown after the others instead of merged with them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sort: Option<MenuSort>,
+}
+/// plain paths show their subdirectories
+fn default_depth() -> usize {
+    1
+}
+impl From<String> for SearchDir {
+    fn from(path: String) -> Self {
+        Self {
+            path,
+            depth: default_depth(),
+            sort: None,
+        }
+    }
+}
+impl Display for SearchDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+impl<'de> serde::Deserialize<'de> for SearchDir {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Path(String),
+            #[serde(with = "SearchDir")]
+            Table(SearchDir),
+        }
+        Ok(match Entry::deserialize(deserializer)? {
+            Entry::Path(path) => path.into(),
+            Entry::Table(dir) => dir,
+        })
+    }
+}
+impl serde::Serialize for SearchDir {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if self.depth == default_depth() && self.sort.is_none() {
+            serializer.serialize_str(&self.path)
+        } else {
+            SearchDir::serialize(self, serializer)
+        }
+    }
+}
+
+/// a configured project, stored either as a plain path or as a table with additional settings
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(remote = "Self")]
+#[schemars(rename = "Project", transform = schema::or_paths)]
+struct Project {
+    #[schemars(schema_with = "schema::paths")]
+    path: String,
+    /// more folders opened together with `path`, stored in `path` as a list
+    #[serde(skip)]
+    more_paths: Vec<String>,
+    /// alternative names to select the project by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    /// command to run before opening, replaces the global pre_open_cmd
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pre_open_cmd: Option<String>,
+    /// command to run inside the project after opening, replaces the global init_cmd
+    #[serde(skip_serializing_if = "Option::is_none")]
+    init_cmd: Option<String>,
+    /// open in an already open editor window, replaces the global reuse_window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reuse_window: Option<bool>,
+    /// command to open the project with, replaces open_cmd and open_rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_cmd: Option<String>,
+    /// groups the project belongs to, all projects of a tag can be opened with [open-tag]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// workspace file relative to the path that is opened instead of the directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workspace_file: Option<String>,
+    /// main language shown with detect_lang and used by --lang, replaces the detected one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lang: Option<String>,
+    /// open the most recently modified file of the project instead of the directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recent_file: Option<bool>,
+    /// run open_cmd with sudo, or as administrator on windows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elevate: Option<bool>,
+}
+impl From<String> for Project {
+    fn from(path: String) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+}
+impl<'de> serde::Deserialize<'de> for Project {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Path(String),
+            Paths(Vec<String>),
+            Table(toml::Table),
+        }
+        /// the first path is the main one
+        fn split<E: Error>(
+            mut paths: Vec<String>,
+        ) -> std::result::Result<(String, Vec<String>), E> {
+            if paths.is_empty() {
+                return Err(E::custom("a project needs at least one path"));
+            }
+            let path = paths.remove(0);
+            Ok((path, paths))
+        }
+        Ok(match Entry::deserialize(deserializer)? {
+            Entry::Path(path) => path.into(),
+            Entry::Paths(paths) => {
+                let (path, more_paths) = split(paths)?;
+                Project {
+                    more_paths,
+                    ..path.into()
+                }
+            }
+            Entry::Table(mut table) => {
+                let mut more_paths = vec![];
+                if let Some(toml::Value::Array(paths)) = table.get("path") {
+                    let paths = paths
+                        .iter()
+                        .map(|p| p.as_str().map(String::from))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| D::Error::custom("paths of a project have to be strings"))?;
+                    let (path, more) = split(paths)?;
+                    table.insert("path".into(), path.into());
+                    more_paths = more;
+                }
+                let mut project =
+                    Project::deserialize(toml::Value::Table(table)).map_err(D::Error::custom)?;
+                project.more_paths = more_paths;
+                project
+            }
+        })
+    }
+}
+impl serde::Serialize for Project {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        /// the table form without the extra paths
+        struct Table<'a>(&'a Project);
+        impl serde::Serialize for Table<'_> {
+            fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+                Project::serialize(self.0, s)
+            }
+        }
+        let paths = || [&self.path].into_iter().chain(&self.more_paths);
+        let only_paths = Project {
+            path: self.path.clone(),
+            more_paths: self.more_paths.clone(),
+            ..Default::default()
+        };
+        // keep the short form if only the paths are set
+        if *self == only_paths && self.more_paths.is_empty() {
+            serializer.serialize_str(&self.path)
+        } else if *self == only_paths {
+            serializer.collect_seq(paths())
+        } else if self.more_paths.is_empty() {
+            Project::serialize(self, serializer)
+        } else {
+            let mut table = toml::Value::try_from(Table(self)).map_err(S::Error::custom)?;
+            table["path"] = paths().cloned().collect::<Vec<_>>().into();
+            table.serialize(serializer)
+        }
+    }
 }
 impl Projects {
     fn new() -> Self {
         Self {
             paths: IndexMap::default(),
+            extra: toml::Table::new(),
             dirs: Some(vec![]),
+            source_cmds: None,
             open_cmd: String::from(""),
+            open_rules: None,
+            reuse_window: Some(false),
+            stdin_path: Some(false),
+            confirm_open: Some(false),
+            pre_open_cmd: None,
+            pre_open_abort: Some(true),
+            init_cmd: None,
+            multiplexer: None,
+            init_cmd_abort: Some(false),
             editor: edit::get_editor()
                 .map(|e| e.to_str().unwrap_or("").into())
                 .unwrap_or("".into()),
+            template_dirs: None,
             sort: Some(true),
+            sort_case_sensitive: Some(false),
+            menu_sort: None,
             exclude_proj_dirs: Some(false),
+            normalize_names: Some(false),
+            scan_timeout_ms: None,
+            resolve_git_root: Some(false),
+            loop_menu: Some(false),
+            esc_exits: Some(false),
+            log_open_output: Some(false),
+            log_sessions: Some(false),
+            show_paths: Some(false),
+            hide_missing: Some(false),
+            detect_lang: Some(false),
+            show_git: Some(false),
+            theme: None,
         }
     }
 }
@@ -52,299 +368,2682 @@ struct Flags {
     #[arg(short, long)]
     print: bool,
 
-    /// use alternative configuration file at `$HOME/.config/wspick/<name>.toml`
+    /// like --print but terminate the path with a NUL byte instead of a newline
+    #[arg(long)]
+    print0: bool,
+
+    /// use alternative configuration file at `$HOME/.config/wspick/<name>.toml`, or at the given
+    /// path if it contains a `/` or ends with `.toml`. Takes precedence over WSPICK_CONFIG
     #[arg(short, long)]
     config: Option<String>,
 
-    /// chose [new], [edit] or a path directly, without opening the selector
+    /// print the number of projects that can be selected and exit
+    #[arg(long)]
+    count: bool,
+
+    /// print the entries of the menu including the actions in their order and exit
+    #[arg(long)]
+    print_menu: bool,
+
+    /// output of [list]
+    #[arg(long, value_enum, default_value_t = ListFormat::Plain)]
+    format: ListFormat,
+
+    /// return to the menu after opening a project until the menu is closed with Esc
+    #[arg(short, long = "loop")]
+    loop_menu: bool,
+
+    /// keep projects and dirs added with [new] or [new dir] only for this run
+    #[arg(long)]
+    no_save: bool,
+
+    /// socket to listen on for [serve], defaults to `wspick.sock` in the data directory
+    #[arg(long)]
+    socket: Option<PathBuf>,
+
+    /// don't create a config file if it doesn't exist, use the defaults for this run instead
+    #[arg(long)]
+    no_init: bool,
+
+    /// print errors as a single `<code>: <message>` line and skip the interactive repair of invalid configs
+    #[arg(long)]
+    porcelain: bool,
+
+    /// write errors to `last_error.txt` in the data directory, show them with [last-error]
+    #[arg(long)]
+    record_errors: bool,
+
+    /// fail instead of warning when project names or aliases are ambiguous
+    #[arg(long)]
+    strict: bool,
+
+    /// print the config with all defaults and flags applied and exit without changing any file
+    #[arg(long)]
+    dump_effective_config: bool,
+
+    /// print the commands, project names and aliases one per line for shell completions
+    #[arg(long, hide = true)]
+    list_names: bool,
+
+    /// open the project directly if only one matches the given name, otherwise show the menu
+    #[arg(long)]
+    first: bool,
+
+    #[command(flatten)]
+    menu: MenuFlags,
+
+    /// open the menu with the query already typed in, combine with --first to open a single match
+    #[arg(long, value_name = "QUERY", visible_alias = "filter")]
+    select: Option<String>,
+
+    /// create the path given to [new] if it doesn't exist without asking
+    #[arg(long)]
+    create: bool,
+
+    /// name of the project added with [add] instead of asking for it
+    #[arg(long)]
+    name: Option<String>,
+
+    /// overwrite an existing config file with [init-config] or [export]
+    #[arg(long)]
+    force: bool,
+
+    /// never start any process, print the commands that would run instead
+    #[arg(long, visible_alias = "dry-run", alias = "stdout-only")]
+    no_exec: bool,
+
+    /// open the most recently modified file of the project instead of the directory
+    #[arg(long)]
+    recent_file: bool,
+
+    /// run open_cmd with sudo, or as administrator on windows
+    #[arg(long)]
+    sudo: bool,
+
+    /// print which entries of dirs are skipped while searching for projects and why,
+    /// with [version] the paths and state of the config
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// chose [new], [edit], [manage], [palette], [cd], [shell-init], [serve], [open-tag], [which], [init-config], [last-error], [export], [import], [version], [schema], [prune], [completions], [list], [add], [check] or a path directly, without opening the selector
     cmd_or_path: Option<String>,
-    /// path for project if given after [new] command
+    /// path for project if given after [new] command, project to change the path of after [edit],
+    /// project to print after [cd] or [which], shell to generate the `wscd` function
+    /// or completions for after [shell-init] or [completions],
+    /// tag to open after [open-tag] or file to write to or read from after [export] and [import]
     new_path: Option<String>,
 }
 
+/// output format of [list]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ListFormat {
+    /// `name<TAB>path` lines
+    Plain,
+    /// array of objects with `name` and `path`
+    Json,
+    /// only the names
+    NamesOnly,
+}
+
+/// flags changing how the menu is shown
+#[derive(Args, Debug, Clone)]
+struct MenuFlags {
+    /// show the path of each project next to its name in the menu
+    #[arg(long)]
+    show_paths: bool,
+
+    /// hide configured projects whose path doesn't exist from the menu
+    #[arg(long)]
+    existing_only: bool,
+
+    /// order of the projects found in dirs, configured projects keep their position with mtime
+    #[arg(long, value_enum)]
+    sort: Option<MenuSort>,
+
+    /// only show projects with this main language, e.g. rust, go or js
+    #[arg(long)]
+    lang: Option<String>,
+
+    /// only show projects opened within this time, e.g. 30m, 12h or 7d
+    #[arg(long, value_name = "DURATION", value_parser = history::parse_duration)]
+    since: Option<Duration>,
+
+    /// only show configured projects with this tag, given more than once projects need all tags
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+}
+
 fn main() -> Result<()> {
     let flags = Flags::parse();
+    let (porcelain, record_errors) = (flags.porcelain, flags.record_errors);
+    let locations = locations(flags.config.as_deref());
+    let result = run(flags);
+    if let Err(err) = &result {
+        if record_errors {
+            if let Ok((_, data_dir)) = locations {
+                error::record(err, &data_dir.join(LAST_ERROR_FILE));
+            }
+        }
+        if porcelain {
+            error::print_porcelain(err);
+            std::process::exit(error::exit_code(err));
+        }
+        if error::exit_code(err) != 1 {
+            // same output as returning the error, but with the exit code of the command
+            eprintln!("Error: {err:?}");
+            std::process::exit(error::exit_code(err));
+        }
+    }
+    result
+}
+
+/// file in the data directory errors are recorded to with `--record-errors`
+const LAST_ERROR_FILE: &str = "last_error.txt";
+
+/// environment variable with the path of the config file to use
+const CONFIG_ENV: &str = "WSPICK_CONFIG";
+
+fn project_dirs() -> Option<directories::ProjectDirs> {
+    directories::ProjectDirs::from("io.github", "mnlphlp", "wspick")
+}
+
+/// paths and the state of the config for bug reports, works without a valid config
+fn print_diagnostics(config_name: Option<&str>, config_file: &Path, data_dir: &Path) {
+    let source = match (config_name, std::env::var_os(CONFIG_ENV)) {
+        (Some(name), _) => format!("--config {name}"),
+        (None, Some(_)) => CONFIG_ENV.to_string(),
+        (None, None) => "default".to_string(),
+    };
+    println!("os: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    println!("config file: {} ({source})", config_file.display());
+    println!("data directory: {}", data_dir.display());
+    let config = match config_file.try_exists() {
+        Ok(true) => load_config(config_file),
+        Ok(false) => {
+            println!("config: not created yet");
+            return;
+        }
+        Err(err) => Err(err.into()),
+    };
+    match config {
+        Ok(config) => {
+            let count = |list: Option<usize>| list.unwrap_or(0);
+            println!(
+                "config: {} projects, {} dirs, {} source_cmds",
+                config.paths.len(),
+                count(config.dirs.as_ref().map(Vec::len)),
+                count(config.source_cmds.as_ref().map(Vec::len)),
+            );
+            if !config.extra.is_empty() {
+                let keys = config.extra.keys().cloned().collect::<Vec<_>>();
+                println!("unknown keys: {}", keys.join(", "));
+            }
+        }
+        Err(err) => println!("config: {err:#}"),
+    }
+}
+
+/// config file and data directory, without a home directory only paths given with `--config` or
+/// `WSPICK_CONFIG` work and the data is kept next to the config file
+fn locations(config: Option<&str>) -> Result<(PathBuf, PathBuf)> {
+    let dirs = project_dirs();
+    let config_file = match (config, std::env::var_os(CONFIG_ENV), &dirs) {
+        (Some(path), _, _) if path.contains(['/', '\\']) || path.ends_with(".toml") => {
+            expand_path(path)
+        }
+        (Some(name), _, Some(dirs)) => dirs.config_dir().join(format!("{}.toml", name)),
+        (None, Some(file), _) => PathBuf::from(file),
+        (None, None, Some(dirs)) => dirs.config_dir().join("wspick.toml"),
+        _ => anyhow::bail!(
+            "no home directory found to store the config in, \
+             set {CONFIG_ENV} to the path of a config file instead"
+        ),
+    };
+    let data_dir = match &dirs {
+        Some(dirs) => dirs.data_dir().to_path_buf(),
+        None => config_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    Ok((config_file, data_dir))
+}
+
+fn run(flags: Flags) -> Result<()> {
+    NO_EXEC.store(flags.no_exec, Ordering::Relaxed);
+    RECENT_FILE.store(flags.recent_file, Ordering::Relaxed);
+    VERBOSE.store(flags.verbose, Ordering::Relaxed);
+    ELEVATE.store(flags.sudo, Ordering::Relaxed);
+    STRICT.store(flags.strict, Ordering::Relaxed);
+    let (config_file, data_dir) = locations(flags.config.as_deref())?;
+    let data_dir = data_dir.as_path();
+    history::init(&config_file);
+    if flags.cmd_or_path.as_deref() == Some("last-error") {
+        // works without a valid config
+        match fs::read_to_string(data_dir.join(LAST_ERROR_FILE)) {
+            Ok(last_error) => print!("{last_error}"),
+            Err(_) => println!("no error recorded"),
+        }
+        return Ok(());
+    }
+    if flags.cmd_or_path.as_deref() == Some("version") {
+        println!("wspick {}", env!("CARGO_PKG_VERSION"));
+        if flags.verbose {
+            print_diagnostics(flags.config.as_deref(), &config_file, data_dir);
+        }
+        return Ok(());
+    }
+    if flags.cmd_or_path.as_deref() == Some("completions") {
+        let shell = flags.new_path.ok_or_else(|| {
+            anyhow::anyhow!("missing shell, use bash, zsh, fish, elvish or powershell")
+        })?;
+        print!("{}", shell::completions(&shell)?);
+        return Ok(());
+    }
+    if flags.cmd_or_path.as_deref() == Some("schema") {
+        let schema = schema::schema()?;
+        write_stdout(|out| writeln!(out, "{schema}"))?;
+        return Ok(());
+    }
+    if flags.cmd_or_path.as_deref() == Some("init-config") {
+        if config_file.try_exists()? && !flags.force {
+            anyhow::bail!(
+                "'{}' already exists, use --force to overwrite it",
+                config_file.display()
+            );
+        }
+        fs::create_dir_all(config_file.parent().unwrap())?;
+        fs::write(
+            &config_file,
+            example::example_config(&Projects::new().editor),
+        )?;
+        println!("wrote example config to '{}'", config_file.display());
+        return Ok(());
+    }
+    if flags.list_names {
+        // completions must not create or repair the config
+        let config = match config_file.try_exists()? {
+            true => Some(load_config(&config_file)?),
+            false => None,
+        };
+        let projects = config.as_ref().map(list_projects).transpose()?;
+        let aliases = config
+            .iter()
+            .flat_map(|c| c.paths.values().flat_map(|p| &p.aliases));
+        let names = projects
+            .iter()
+            .flatten()
+            .map(|(name, _)| name)
+            .chain(aliases);
+        for name in COMMANDS.iter().copied().chain(names.map(String::as_str)) {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+    if flags.dump_effective_config {
+        // nothing is written, without a config file the defaults are in effect
+        let mut config = if config_file.try_exists()? {
+            load_config(&config_file)?
+        } else {
+            Projects::new()
+        };
+        fill_defaults(&mut config);
+        if flags.loop_menu {
+            config.loop_menu = Some(true);
+        }
+        if flags.menu.show_paths {
+            config.show_paths = Some(true);
+        }
+        if flags.menu.existing_only {
+            config.hide_missing = Some(true);
+        }
+        if let Some(sort) = flags.menu.sort {
+            config.menu_sort = Some(sort);
+        }
+        // these flags apply to every project
+        for project in config.paths.values_mut() {
+            if flags.sudo {
+                project.elevate = Some(true);
+            }
+            if flags.recent_file {
+                project.recent_file = Some(true);
+            }
+        }
+        println!("# effective config of '{}'", config_file.display());
+        print!("{}", toml::ser::to_string_pretty(&config)?);
+        return Ok(());
+    }
+    if flags.cmd_or_path.as_deref() == Some("check") {
+        return check::check(&config_file, flags.strict);
+    }
     // make sure config exists
-    let dirs = directories::ProjectDirs::from("io.github", "mnlphlp", "wspick")
-        .expect("home directory has to be found");
-    let config_dir = dirs.config_dir();
-    let config_file = if let Some(name) = flags.config {
-        config_dir.join(format!("{}.toml", name))
+    let exists = config_file.try_exists()?;
+    // without a config file on disk all changes only last for this run
+    let save = !flags.no_save && (exists || !flags.no_init);
+    let mut config = if !exists && flags.no_init {
+        Projects::new()
     } else {
-        config_dir.join("wspick.toml")
+        if !exists {
+            save_config(&Projects::new(), &config_file)?;
+        }
+        // load config
+        let mut config = match flags.porcelain {
+            true => load_config(&config_file)?,
+            false => load_or_repair_config(&config_file)?,
+        };
+        // add later added config items
+        update_config(&mut config, &config_file)?;
+        check_names(&config, None, flags.strict)?;
+        validate_theme(&mut config);
+        config
     };
-    if !config_file.try_exists()? {
-        save_config(&Projects::new(), &config_file)?;
+    if flags.count {
+        let menu = Menu::new(&config, &flags.menu);
+        let (entries, _) = menu_entries(&mut config, &menu)?;
+        println!("{}", entries.len() - MENU_ACTIONS.len());
+        return Ok(());
+    }
+    if flags.cmd_or_path.as_deref() == Some("list") {
+        let menu = Menu::new(&config, &flags.menu);
+        return list(&mut config, &menu, flags.format);
+    }
+    if flags.print_menu {
+        let menu = Menu::new(&config, &flags.menu);
+        let (entries, _) = menu_entries(&mut config, &menu)?;
+        for entry in entries {
+            println!("{entry}");
+        }
+        return Ok(());
     }
-    // load config
-    let mut config = load_config(&config_file)?;
-    // add later added config items
-    update_config(&mut config, &config_file)?;
     // check cmd args#
-    let mut path = None;
+    let mut selected = None;
+    let mut filter = flags.select.unwrap_or_default();
     if let Some(cmd) = flags.cmd_or_path {
         match cmd.as_str() {
-            "new" => path = Some(new_project(&mut config, &config_file, flags.new_path)?),
-            "edit" => edit_project(&mut config, &config_file)?,
-            _ => path = Some(cmd),
+            "new" => {
+                if let Some(path) = &flags.new_path {
+                    create_missing_dir(path, flags.create)?;
+                }
+                selected = Some(new_project(
+                    &mut config,
+                    &config_file,
+                    None,
+                    flags.new_path,
+                    save,
+                )?)
+            }
+            "edit" => match flags.new_path {
+                Some(name) => return edit_path(&mut config, &config_file, &name, save),
+                None => edit_project(&mut config, &config_file)?,
+            },
+            "manage" => return manage::manage(&mut config, &config_file, save),
+            "export" => {
+                let file = flags
+                    .new_path
+                    .ok_or_else(|| anyhow::anyhow!("missing file to export to"))?;
+                return bundle::export(&config, Path::new(&file), flags.force);
+            }
+            "import" => {
+                let file = flags
+                    .new_path
+                    .ok_or_else(|| anyhow::anyhow!("missing file to import"))?;
+                return bundle::import(&mut config, &config_file, Path::new(&file), save);
+            }
+            "prune" => return prune(&mut config, &config_file, save),
+            "add" => {
+                let path = flags.new_path.unwrap_or_else(|| ".".into());
+                let (name, path) = add_project(&mut config, &config_file, &path, flags.name, save)?;
+                println!("added '{name}' at '{path}'");
+                return Ok(());
+            }
+            "cd" => {
+                let selected = flags
+                    .new_path
+                    .map(|name| resolve(&config, None, &name))
+                    .transpose()?;
+                let menu = Menu::new(&config, &flags.menu);
+                if let Some((_, path)) =
+                    select_project(&mut config, &config_file, selected, menu, save)?
+                {
+                    println!("{}", cd_dir(&path).display());
+                }
+                return Ok(());
+            }
+            "shell-init" => {
+                let shell = flags.new_path.ok_or_else(|| {
+                    anyhow::anyhow!("missing shell, use bash, zsh, fish or powershell")
+                })?;
+                print!("{}", shell::init_script(&shell)?);
+                return Ok(());
+            }
+            "which" => {
+                let name = flags
+                    .new_path
+                    .ok_or_else(|| anyhow::anyhow!("missing project name"))?;
+                let (_, path) = resolve(&config, None, &name)?;
+                if split_remote(&path).is_some() {
+                    println!("{path}");
+                } else {
+                    println!("{}", fs::canonicalize(expand_path(&path))?.display());
+                }
+                return Ok(());
+            }
+            "palette" => {
+                let menu = Menu::new(&config, &flags.menu);
+                let log_dir = data_dir.join("logs");
+                match palette::palette(&mut config, &config_file, menu, &log_dir, save)? {
+                    Some(project) => selected = Some(project),
+                    None => return Ok(()),
+                }
+            }
+            "action" => {
+                let menu = Menu::new(&config, &flags.menu);
+                let log_dir = data_dir.join("logs");
+                let name = flags.new_path.as_deref();
+                match palette::action(name, &mut config, &config_file, menu, &log_dir, save)? {
+                    Some(project) => selected = Some(project),
+                    None => return Ok(()),
+                }
+            }
+            "open-tag" => {
+                let tag = flags
+                    .new_path
+                    .ok_or_else(|| anyhow::anyhow!("missing tag to open"))?;
+                return open_tag(&config, &tag, flags.print, &data_dir.join("logs"));
+            }
+            #[cfg(unix)]
+            "serve" => {
+                let socket = flags.socket.unwrap_or_else(|| data_dir.join("wspick.sock"));
+                return serve::serve(&mut config, &config_file, &socket, &flags.menu, save);
+            }
+            #[cfg(not(unix))]
+            "serve" => anyhow::bail!("serve is only supported on unix"),
+            _ if flags.first => filter = cmd,
+            _ => selected = Some(resolve(&config, None, &cmd)?),
         }
     }
-    // build and show menu
-    while path.is_none() {
-        let mut options: Vec<String> = config.paths.keys().cloned().collect();
-        let dir_paths = add_options_from_dirs(&mut config, &mut options)?;
-        options.push("[new project]".into());
-        options.push("[new dir]".into());
-        options.push("[edit]".into());
-        let menu = inquire::Select::new("select project:", options)
-            .with_page_size(termsize::get().map(|size| size.rows - 3).unwrap_or(10) as usize);
-        if let Some(selected) = menu.prompt_skippable()? {
-            match config.paths.get(&selected) {
-                None => {
-                    if selected == "[new project]" {
-                        path = Some(new_project(&mut config, &config_file, None)?)
-                    } else if selected == "[new dir]" {
-                        add_dir(&mut config, &config_file)?;
-                    } else if selected == "[edit]" {
-                        edit_project(&mut config, &config_file)?;
-                    } else {
-                        path = Some(
-                            dir_paths
-                                .get(&selected)
-                                .expect("invalid option, this should never happen")
-                                .clone(),
-                        );
+    if flags.first {
+        let menu = Menu::new(&config, &flags.menu);
+        selected = first_match(&mut config, &menu, &filter)?;
+    }
+    loop {
+        let menu = Menu {
+            filter: std::mem::take(&mut filter),
+            ..Menu::new(&config, &flags.menu)
+        };
+        if let Some((name, path)) =
+            select_project(&mut config, &config_file, selected.take(), menu, save)?
+        {
+            if config.log_sessions.unwrap_or(false) {
+                log_session(data_dir, &name)?;
+            }
+            if flags.print0 {
+                // NUL terminated for safe parsing of any path
+                print!("{}\0", open_target(&config, &path));
+                std::io::stdout().flush()?;
+            } else {
+                if !flags.print {
+                    pre_open(&config, &name, &path)?;
+                }
+                let log_dir = data_dir.join("logs");
+                if config.log_open_output.unwrap_or(false) && !flags.print {
+                    prune_open_logs(&log_dir, 1)?;
+                }
+                // in scripts a missing open_cmd stays an error
+                let ask = !flags.porcelain && std::io::stdin().is_terminal();
+                loop {
+                    match open_project(&config, &name, &path, flags.print, &log_dir) {
+                        Err(err) if ask && err.is::<OpenCmdNotFound>() => {
+                            eprintln!("{err}");
+                            if !replace_open_cmd(&mut config, &config_file, save)? {
+                                return Err(err);
+                            }
+                        }
+                        result => {
+                            result?;
+                            break;
+                        }
                     }
                 }
-                Some(val) => path = Some(val.clone()),
+            }
+            // --no-save and --no-init leave no files behind
+            if save && !NO_EXEC.load(Ordering::Relaxed) {
+                // not being able to remember it is no reason to fail
+                if let Err(err) = history::record(&path) {
+                    eprintln!("failed to record '{name}' in the history: {err:#}");
+                }
             }
         } else {
             return Ok(());
         }
-    }
-    open_project(&config.open_cmd, &path.unwrap(), flags.print)?;
-    Ok(())
-}
-
-fn load_config(config_file: &PathBuf) -> Result<Projects> {
-    let mut config: Result<Projects, _> = toml::from_str(&fs::read_to_string(&config_file)?);
-    while let Err(ref err) = config {
-        // display error and ask for action
-        match inquire::Select::new(
-            format!("config file is invalid: {err}\n\nwhat do you want to do?").as_str(),
-            vec!["edit", "generate new", "exit"],
-        )
-        .prompt()?
-        {
-            "edit" => {
-                let mut edited = Projects::new();
-                if edit_project(&mut edited, config_file).is_ok() {
-                    config = Ok(edited)
-                };
-            }
-            "generate new" => {
-                // generate new empty configuration
-                save_config(&Projects::new(), config_file)?;
-                config = Ok(Projects::new())
-            }
-            "exit" => std::process::exit(1),
-            _ => (),
+        if !flags.loop_menu && !config.loop_menu.unwrap_or(false) {
+            return Ok(());
         }
     }
-    Ok(config?)
-}
-
-fn add_dir(config: &mut Projects, config_file: &PathBuf) -> Result<()> {
-    let path = inquire::Text::new("directory path:")
-        .with_validator(FileValidator)
-        .prompt()?;
-    if config.dirs.is_none() {
-        config.dirs = Some(vec![])
-    }
-    config.dirs.as_mut().unwrap().push(path);
-    sort_config(config);
-    save_config(config, config_file)?;
-    Ok(())
 }
 
-fn add_options_from_dirs(
+/// show the menu until a project is selected, returns name and path or `None` if the menu was closed
+fn select_project(
     config: &mut Projects,
-    options: &mut Vec<String>,
-) -> Result<HashMap<String, String>> {
-    let mut map = HashMap::new();
-    if let Some(dirs) = config.dirs.as_ref() {
-        for dir in dirs {
-            let dir_path = PathBuf::from(dir);
-            let dir_name = dir_path.file_name().map(|d| d.to_str());
-            if dir_name.is_none() || dir_name.unwrap().is_none() {
-                continue;
-            }
-            // filter for directories
-            let mut paths = fs::read_dir(dir)?
-                .filter(|f| {
-                    if f.is_err() {
-                        return false;
-                    }
-                    if let Ok(ft) = f.as_ref().unwrap().file_type() {
-                        return ft.is_dir();
-                    }
-                    false
-                })
+    config_file: &PathBuf,
+    mut project: Option<(String, String)>,
+    mut menu: Menu,
+    save: bool,
+) -> Result<Option<(String, String)>> {
+    // build and show menu
+    while project.is_none() {
+        let (options, dir_paths) = menu_entries(config, &menu)?;
+        let score_entry =
+            |input: &str, option: &String, idx: usize| score_project(config, input, option, idx);
+        let query = RefCell::new(String::new());
+        let entries = options.clone();
+        let columns = menu
+            .show_paths
+            .then(|| path_columns(config, &dir_paths, &entries));
+        let git = menu.show_git.then(|| {
+            let projects = entries
+                .iter()
+                .filter_map(|e| Some((e.clone(), option_path(config, &dir_paths, e)?.into())))
                 .collect::<Vec<_>>();
-            if let Some(true) = config.exclude_proj_dirs {
-                // filter out directories that contain projects
-                paths.retain(|p| {
-                    if let Ok(p) = p {
-                        let name = p.file_name().to_string_lossy().to_string();
-                        // filter custom project paths
-                        for proj in config.paths.values() {
-                            if proj.contains(&name) {
-                                return false;
-                            }
-                        }
-                        // filter searched dirs
-                        if let Some(dirs) = &config.dirs {
-                            for dir in dirs {
-                                if dir.contains(&name) {
-                                    return false;
-                                }
-                            }
-                        }
-                    }
-                    true
-                });
-            }
-            for path in paths {
-                if let Ok(path) = path.map(|p| p.path()) {
-                    let path_str = path.to_str();
-                    let name = path.file_name().map(|n| n.to_str());
-                    if path_str.is_none()
-                        || name.is_none()
-                        || name.unwrap().is_none()
-                        || name.unwrap().unwrap().starts_with('.')
-                    {
-                        continue;
+            git::annotations(&projects)
+        });
+        let options = options
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let mut suffix = columns.as_ref().and_then(|c| c[i].clone());
+                if menu.show_lang {
+                    let lang = option_path(config, &dir_paths, &entry)
+                        .and_then(|path| lang::project_lang(config, &entry, path));
+                    if let Some(lang) = lang {
+                        let tag = format!(" [{lang}]").dim().to_string();
+                        suffix = Some(suffix.unwrap_or_default() + &tag);
                     }
-                    let key = String::from(name.unwrap().unwrap());
-                    options.push(key.clone());
-                    map.insert(key, path_str.unwrap().into());
                 }
+                if let Some(status) = git.as_ref().and_then(|git| git.get(&entry)) {
+                    let tag = format!(" {status}").dim().to_string();
+                    suffix = Some(suffix.unwrap_or_default() + &tag);
+                }
+                MenuOption::Entry(entry, suffix)
+            })
+            .chain([MenuOption::Create(&query)])
+            .collect();
+        let scorer = |input: &str, option: &MenuOption, _: &str, idx: usize| match option {
+            MenuOption::Entry(entry, _) => score_entry(input, entry, idx),
+            // only offered if nothing else matches
+            MenuOption::Create(_) => {
+                query.replace(input.to_string());
+                let matched = entries
+                    .iter()
+                    .enumerate()
+                    .any(|(i, e)| score_entry(input, e, i).is_some());
+                (!input.is_empty() && !matched).then_some(0)
+            }
+        };
+        let theme = config.theme.clone().unwrap_or_default();
+        let mut select = inquire::Select::new("select project:", options)
+            .with_page_size(page_size())
+            .with_scorer(&scorer)
+            .with_render_config(theme.render_config())
+            .with_starting_filter_input(&menu.filter);
+        if !theme.show_help() {
+            select = select.without_help_message();
+        }
+        let Some(selected) = select.prompt_skippable()? else {
+            return Ok(None);
+        };
+        let selected = match selected {
+            MenuOption::Entry(entry, _) => entry,
+            MenuOption::Create(query) => {
+                let name = query.take();
+                let result = new_project(config, config_file, Some(name), None, save);
+                match back_on_esc(config, result)? {
+                    Back::Done(selected) => project = Some(selected),
+                    Back::Menu => (),
+                    Back::Exit => return Ok(None),
+                }
+                continue;
+            }
+        };
+        if let Some(val) = config.paths.get(&selected) {
+            project = Some((selected.clone(), val.path.clone()));
+            continue;
+        }
+        let result = match selected.as_str() {
+            "[new project]" => new_project(config, config_file, None, None, save).map(Some),
+            "[rename project]" => rename_selected(config, config_file, save).map(|_| None),
+            "[filter by tag]" => select_tags(config, &menu.tags).map(|tags| {
+                if let Some(tags) = tags {
+                    menu.tags = tags;
+                }
+                None
+            }),
+            "[delete project]" => delete_projects(config, config_file, save).map(|_| None),
+            "[preview readme]" => preview_readme(config, &entries, &dir_paths).map(|_| None),
+            "[new dir]" => add_dir(config, config_file, save).map(|_| None),
+            "[manage dirs]" => remove_dirs(config, config_file, save).map(|_| None),
+            "[edit]" => edit_project(config, config_file).map(|_| None),
+            _ => {
+                let path = dir_paths
+                    .get(&selected)
+                    .expect("invalid option, this should never happen")
+                    .clone();
+                Ok(Some((selected, path)))
             }
+        };
+        match back_on_esc(config, result)? {
+            Back::Done(selected) => project = selected,
+            Back::Menu => (),
+            Back::Exit => return Ok(None),
         }
-        options.sort();
     }
-    Ok(map)
+    Ok(project)
 }
 
-fn update_config(config: &mut Projects, config_file: &PathBuf) -> Result<()> {
-    let mut changed = false;
-    if config.sort.is_none() {
-        config.sort = Some(true);
-        sort_config(config);
-        changed = true;
+/// the entries of the menu in their order, followed by the actions, and the paths of found projects
+fn menu_entries(
+    config: &mut Projects,
+    menu: &Menu,
+) -> Result<(Vec<String>, HashMap<String, String>)> {
+    let mut options: Vec<String> = config
+        .paths
+        .iter()
+        .filter(|(_, p)| {
+            !menu.hide_missing || split_remote(&p.path).is_some() || expand_path(&p.path).exists()
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    let mut separate = vec![];
+    let dir_paths = add_options_from_dirs(config, &mut options, &mut separate)?;
+    match menu.sort {
+        MenuSort::Name => (),
+        MenuSort::Mtime => sort_by_mtime(config, &dir_paths, &mut options),
+        MenuSort::Recent => sort_by_recent(config, &dir_paths, &mut options),
     }
-    if config.dirs.is_none() {
-        config.dirs = Some(vec![]);
-        changed = true;
+    options.extend(separate.into_iter().flatten());
+    if let Some(lang) = &menu.lang {
+        options.retain(|name| {
+            let path = option_path(config, &dir_paths, name).unwrap_or_default();
+            lang::project_lang(config, name, path).is_some_and(|l| l.eq_ignore_ascii_case(lang))
+        });
     }
-    if config.exclude_proj_dirs.is_none() {
-        config.exclude_proj_dirs = Some(false);
-        changed = true;
+    if let Some(since) = menu.since {
+        // projects never opened are left out as well
+        let recent = history::opened_since(since);
+        options.retain(|name| {
+            option_path(config, &dir_paths, name)
+                .is_some_and(|path| recent.iter().any(|r| r == path))
+        });
     }
-    if changed {
-        save_config(config, config_file)?;
+    if !menu.tags.is_empty() {
+        // found projects have no tags
+        options.retain(|name| {
+            config
+                .paths
+                .get(name)
+                .is_some_and(|p| menu.tags.iter().all(|tag| p.tags.contains(tag)))
+        });
     }
-    Ok(())
+    options.extend(MENU_ACTIONS.map(String::from));
+    Ok((options, dir_paths))
 }
 
-fn save_config(config: &Projects, config_file: &PathBuf) -> Result<()> {
-    let doc = toml::ser::to_string_pretty(config)?;
-    let mut doc_commented = vec![];
-    // add comments
-    for line in doc.lines() {
-        match &line[..line.find(' ').unwrap_or(line.len())] {
-            "open_cmd" => {
-                doc_commented.push(format!("# {}", Projects::get_docs().open_cmd));
-            }
-            "sort" => {
-                doc_commented.push(format!("# {}", Projects::get_docs().sort));
-            }
-            "exclude_proj_dirs" => {
-                doc_commented.push(format!("# {}", Projects::get_docs().exclude_proj_dirs));
-            }
-            "[paths]" => {
-                doc_commented.push(format!("# {}", Projects::get_docs().paths));
+/// print the projects of the menu in its order without the actions
+fn list(config: &mut Projects, menu: &Menu, format: ListFormat) -> Result<()> {
+    let (entries, dir_paths) = menu_entries(config, menu)?;
+    let projects = entries
+        .iter()
+        .filter_map(|name| Some((name, expand(option_path(config, &dir_paths, name)?))))
+        .collect::<Vec<_>>();
+    write_stdout(|out| {
+        match format {
+            ListFormat::Plain => {
+                for (name, path) in projects {
+                    writeln!(out, "{name}\t{path}")?;
+                }
             }
-            "dirs" => {
-                doc_commented.push(format!("# {}", Projects::get_docs().dirs));
+            ListFormat::Json => {
+                let projects = projects
+                    .into_iter()
+                    .map(|(name, path)| serde_json::json!({ "name": name, "path": path }))
+                    .collect::<Vec<_>>();
+                writeln!(out, "{}", serde_json::to_string_pretty(&projects)?)?;
             }
-            "editor" => {
-                doc_commented.push(format!("# {}", Projects::get_docs().editor));
+            ListFormat::NamesOnly => {
+                for (name, _) in projects {
+                    writeln!(out, "{name}")?;
+                }
             }
-            _ => (),
         }
-        doc_commented.push(line.to_string())
-    }
-    fs::create_dir_all(config_file.parent().unwrap())?;
-    fs::write(config_file, doc_commented.join("\n"))?;
-    Ok(())
+        Ok(())
+    })
 }
 
-fn open_project(cmd: &str, path: &str, print: bool) -> Result<()> {
-    if print || cmd.is_empty() {
-        println!("{path}");
-    } else {
-        Command::new(cmd).arg(path).spawn()?.wait()?;
+/// write to the locked stdout with `write`. A closed pipe, like with `| head -1`, is no error
+fn write_stdout(write: impl FnOnce(&mut std::io::StdoutLock) -> std::io::Result<()>) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    match write(&mut stdout).and_then(|_| stdout.flush()) {
+        Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => Err(err.into()),
+        _ => Ok(()),
     }
-    Ok(())
 }
 
-#[derive(Clone)]
-struct FileValidator;
-impl StringValidator for FileValidator {
-    fn validate(
-        &self,
-        input: &str,
-    ) -> std::result::Result<inquire::validator::Validation, inquire::CustomUserError> {
-        match Path::new(input).try_exists() {
-            Ok(val) => {
-                if val {
-                    Ok(Validation::Valid)
-                } else {
-                    Ok(Validation::Invalid(ErrorMessage::Custom(format!(
-                        "path '{input}' does not exist"
-                    ))))
-                }
-            }
-            Err(e) => Err(CustomUserError::from(e)),
-        }
-    }
+/// where to continue after a submenu
+enum Back<T> {
+    /// the submenu was completed with this result
+    Done(T),
+    /// Esc was pressed, show the previous menu again
+    Menu,
+    /// Esc was pressed and `esc_exits` is set
+    Exit,
+}
+
+/// handle Esc pressed in a submenu, other errors are passed on
+fn back_on_esc<T>(config: &Projects, result: Result<T>) -> Result<Back<T>> {
+    match result {
+        Ok(value) => Ok(Back::Done(value)),
+        Err(err) if is_canceled(&err) && config.esc_exits.unwrap_or(false) => Ok(Back::Exit),
+        Err(err) if is_canceled(&err) => Ok(Back::Menu),
+        Err(err) => Err(err),
+    }
+}
+
+/// whether the error is Esc pressed in a prompt, Ctrl-C is not
+fn is_canceled(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<inquire::InquireError>(),
+        Some(inquire::InquireError::OperationCanceled)
+    )
+}
+
+/// match aliases of configured projects as well as the displayed name
+fn score_project(config: &Projects, input: &str, name: &String, idx: usize) -> Option<i64> {
+    let score = inquire::Select::<String>::DEFAULT_SCORER;
+    let aliases = config.paths.get(name).map(|p| p.aliases.as_slice());
+    aliases
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|alias| score(input, name, alias, idx))
+        .chain(score(input, name, name, idx))
+        .max()
+}
+
+/// the project to open for `--first`, `None` if more than one matches the filter. Only projects
+/// shown in the menu with the filters of `menu` are candidates
+fn first_match(
+    config: &mut Projects,
+    menu: &Menu,
+    filter: &str,
+) -> Result<Option<(String, String)>> {
+    // anything the name resolves to on its own always wins
+    if !filter.is_empty() {
+        if let Ok(project) = resolve(config, None, filter) {
+            return Ok(Some(project));
+        }
+    }
+    let (entries, dir_paths) = menu_entries(config, menu)?;
+    let mut matches = entries
+        .into_iter()
+        .filter_map(|name| {
+            let path = option_path(config, &dir_paths, &name)?.to_string();
+            Some((name, path))
+        })
+        .enumerate()
+        .filter(|(idx, (name, _))| score_project(config, filter, name, *idx).is_some())
+        .map(|(_, project)| project);
+    match (matches.next(), matches.next()) {
+        (None, _) => anyhow::bail!("no project matches '{filter}'"),
+        (Some(project), None) => Ok(Some(project)),
+        _ => Ok(None),
+    }
+}
+
+/// number of menu entries that fit in the terminal, queried again every time the menu is built
+/// so a resized terminal is picked up when returning to the menu
+fn page_size() -> usize {
+    termsize::get()
+        .map(|size| size.rows.saturating_sub(3).max(1))
+        .unwrap_or(10) as usize
+}
+
+/// how the selection menu is shown
+#[derive(Clone)]
+struct Menu {
+    /// initial search query
+    filter: String,
+    /// show the path next to each project
+    show_paths: bool,
+    /// order of the found projects
+    sort: MenuSort,
+    /// hide configured projects with missing paths
+    hide_missing: bool,
+    /// show the language of each project
+    show_lang: bool,
+    /// show the branch and state of git repositories
+    show_git: bool,
+    /// only show projects of this language
+    lang: Option<String>,
+    /// only show projects opened within this time
+    since: Option<Duration>,
+    /// only show configured projects with all of these tags
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum MenuSort {
+    /// alphabetically together with the configured projects
+    Name,
+    /// most recently modified first, after the configured projects
+    Mtime,
+    /// most recently opened first together with the configured projects, the others by name
+    Recent,
+}
+impl Menu {
+    /// menu with the configured settings, enabled by the flags of this run as well
+    fn new(config: &Projects, flags: &MenuFlags) -> Self {
+        Self {
+            filter: String::new(),
+            show_paths: flags.show_paths || config.show_paths.unwrap_or(false),
+            sort: flags.sort.or(config.menu_sort).unwrap_or(MenuSort::Name),
+            hide_missing: flags.existing_only || config.hide_missing.unwrap_or(false),
+            show_lang: config.detect_lang.unwrap_or(false),
+            show_git: config.show_git.unwrap_or(false),
+            lang: flags.lang.clone(),
+            since: flags.since,
+            tags: flags.tags.clone(),
+        }
+    }
+}
+
+/// path of a menu entry, `None` for actions
+fn option_path<'a>(
+    config: &'a Projects,
+    dir_paths: &'a HashMap<String, String>,
+    name: &str,
+) -> Option<&'a str> {
+    match config.paths.get(name) {
+        Some(project) => Some(&project.path),
+        None => dir_paths.get(name).map(String::as_str),
+    }
+}
+
+/// padding and dimmed path shown after each entry with a path, `None` for actions
+fn path_columns(
+    config: &Projects,
+    dir_paths: &HashMap<String, String>,
+    entries: &[String],
+) -> Vec<Option<String>> {
+    let name_width = entries.iter().map(|e| e.chars().count()).max().unwrap_or(0);
+    let cols = termsize::get().map(|s| s.cols as usize).unwrap_or(80);
+    // leave room for the cursor prefix and the gap between the columns
+    let path_width = cols.saturating_sub(name_width + 6).max(10);
+    entries
+        .iter()
+        .map(|entry| {
+            let path = option_path(config, dir_paths, entry)?;
+            let padding = " ".repeat(name_width - entry.chars().count() + 4);
+            Some(format!(
+                "{padding}{}",
+                truncate_middle(path, path_width).dim()
+            ))
+        })
+        .collect()
+}
+
+/// shorten `text` to `max` chars by replacing the middle with `...`
+fn truncate_middle(text: &str, max: usize) -> String {
+    let len = text.chars().count();
+    if len <= max {
+        return text.to_string();
+    }
+    let keep = max.saturating_sub(3);
+    let head = text.chars().take(keep / 2);
+    let tail = text.chars().skip(len - (keep - keep / 2));
+    head.chain("...".chars()).chain(tail).collect()
+}
+
+/// entry of the selection menu
+enum MenuOption<'a> {
+    /// project or action and the path column if paths are shown
+    Entry(String, Option<String>),
+    /// create a project named like the current query
+    Create(&'a RefCell<String>),
+}
+impl Display for MenuOption<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MenuOption::Entry(entry, path) => {
+                f.write_str(entry)?;
+                f.write_str(path.as_deref().unwrap_or_default())
+            }
+            MenuOption::Create(query) => write!(f, "[create \"{}\"]", query.borrow()),
+        }
+    }
+}
+
+/// parse the config without asking anything, invalid configs are an error
+fn load_config(config_file: &Path) -> Result<Projects> {
+    let content = fs::read_to_string(config_file)?;
+    toml::from_str(&content).map_err(|err| {
+        CodedError::new(
+            ErrorCode::ConfigInvalid,
+            format!("config file '{}' is invalid: {err}", config_file.display()),
+        )
+        .into()
+    })
+}
+
+/// parse the config and ask how to fix it while it is invalid
+fn load_or_repair_config(config_file: &PathBuf) -> Result<Projects> {
+    const EDIT: &str = "edit";
+    const OPEN_DIR: &str = "open config directory";
+    const GENERATE: &str = "generate new";
+    const EXIT: &str = "exit";
+    loop {
+        let content = fs::read_to_string(config_file)?;
+        let err = match toml::from_str(&content) {
+            Ok(config) => return Ok(config),
+            Err(err) => err,
+        };
+        let position = error_position(&content, &err);
+        // the line is only offered if the editor can jump there
+        let editor = Projects::new().editor;
+        let edit_at = position
+            .filter(|&(line, column)| goto_line_args(&editor, config_file, line, column).is_some())
+            .map(|(line, _)| format!("edit at line {line}"));
+        let options = edit_at
+            .iter()
+            .map(String::as_str)
+            .chain([EDIT, OPEN_DIR, GENERATE, EXIT])
+            .collect();
+        // display error and ask for action
+        let result = match inquire::Select::new(
+            // the error already names line and column
+            format!(
+                "config file '{}' is invalid: {err}\n\nwhat do you want to do?",
+                config_file.display()
+            )
+            .as_str(),
+            options,
+        )
+        .prompt()?
+        {
+            EDIT => run_editor(&editor, config_file, None).map(|_| ()),
+            OPEN_DIR => open_dir(config_file.parent().unwrap_or(Path::new("."))),
+            GENERATE => {
+                // generate new empty configuration
+                save_config(&Projects::new(), config_file)?;
+                return Ok(Projects::new());
+            }
+            EXIT => std::process::exit(1),
+            _ => run_editor(&editor, config_file, position).map(|_| ()),
+        };
+        if let Err(err) = result {
+            eprintln!("{err:#}");
+        }
+    }
+}
+
+/// line and column of a parse error in `content`
+fn error_position(content: &str, err: &toml::de::Error) -> Option<(usize, usize)> {
+    err.span().map(|span| line_column(content, span.start))
+}
+
+/// 1-based line and column of a byte offset in `text`
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// arguments to open `file` at a position with editors known to support it, `None` otherwise
+fn goto_line_args(editor: &str, file: &Path, line: usize, column: usize) -> Option<Vec<String>> {
+    let (program, mut args) = split_cmd(editor, "editor").ok()??;
+    let file = file.to_string_lossy();
+    match Path::new(&program).file_stem()?.to_str()? {
+        "vi" | "vim" | "nvim" | "nano" | "emacs" | "emacsclient" | "kak" | "micro" => {
+            args.extend([format!("+{line}"), file.to_string()])
+        }
+        "hx" | "helix" => args.push(format!("{file}:{line}:{column}")),
+        "code" | "code-insiders" | "codium" => {
+            args.extend(["--goto".into(), format!("{file}:{line}:{column}")])
+        }
+        _ => return None,
+    }
+    Some([program].into_iter().chain(args).collect())
+}
+
+/// open a directory in the file manager of the platform
+fn open_dir(dir: &Path) -> Result<()> {
+    let opener = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let mut command = Command::new(opener);
+    command.arg(dir);
+    if dry_run(&command) {
+        return Ok(());
+    }
+    // explorer exits with 1 even on success
+    command
+        .status()
+        .map_err(|e| not_found_as(e, ErrorCode::CmdNotFound, opener))?;
+    Ok(())
+}
+
+fn add_dir(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<()> {
+    let path = inquire::Text::new("directory path:")
+        .with_validator(FileValidator)
+        .prompt()?;
+    if config.dirs.is_none() {
+        config.dirs = Some(vec![])
+    }
+    // compare canonical paths to catch trailing slashes, `..` and symlinks
+    let canonical = fs::canonicalize(expand_path(&path))?;
+    for dir in config.dirs.iter().flatten() {
+        let Ok(existing) = fs::canonicalize(expand_path(&dir.path)) else {
+            continue;
+        };
+        if existing == canonical {
+            println!("'{path}' is already configured as '{dir}'");
+            return Ok(());
+        }
+        if canonical.starts_with(&existing) {
+            eprintln!("warning: '{path}' is inside the already configured '{dir}'");
+        }
+    }
+    config.dirs.as_mut().unwrap().push(path.into());
+    sort_config(config);
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok(())
+}
+
+/// select directories to remove from `dirs`
+fn remove_dirs(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<()> {
+    let dirs = config.dirs.clone().unwrap_or_default();
+    if dirs.is_empty() {
+        println!("no dirs configured, add one with [new dir]");
+        return Ok(());
+    }
+    let Some(remove) = inquire::MultiSelect::new("select dirs to remove:", dirs)
+        .with_page_size(page_size())
+        .prompt_skippable()?
+    else {
+        return Ok(());
+    };
+    if remove.is_empty() {
+        return Ok(());
+    }
+    config
+        .dirs
+        .as_mut()
+        .unwrap()
+        .retain(|dir| !remove.contains(dir));
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok(())
+}
+
+/// ask for the tags projects in the menu need to have, `None` if Esc was pressed. Selecting none
+/// shows all projects again
+fn select_tags(config: &Projects, current: &[String]) -> Result<Option<Vec<String>>> {
+    let mut tags = config
+        .paths
+        .values()
+        .flat_map(|p| p.tags.iter().cloned())
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+    if tags.is_empty() {
+        println!("no project has tags, add them with `tags` in the config");
+        return Ok(None);
+    }
+    let selected = tags
+        .iter()
+        .enumerate()
+        .filter(|(_, tag)| current.contains(tag))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    Ok(
+        inquire::MultiSelect::new("show projects with the tags:", tags)
+            .with_page_size(page_size())
+            .with_default(&selected)
+            .prompt_skippable()?,
+    )
+}
+
+/// print the readme of a project in the menu
+fn preview_readme(
+    config: &Projects,
+    entries: &[String],
+    dir_paths: &HashMap<String, String>,
+) -> Result<()> {
+    let projects: Vec<String> = entries
+        .iter()
+        .filter(|entry| !MENU_ACTIONS.contains(&entry.as_str()))
+        .cloned()
+        .collect();
+    if projects.is_empty() {
+        println!("no projects in the menu, add one with [new project]");
+        return Ok(());
+    }
+    let name = inquire::Select::new("preview readme of:", projects)
+        .with_page_size(page_size())
+        .prompt()?;
+    let path = option_path(config, dir_paths, &name).unwrap_or_default();
+    preview::print_readme(&name, path)
+}
+
+/// rename a configured project, keeping its position unless the projects are sorted
+fn rename_selected(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<()> {
+    let names: Vec<String> = config.paths.keys().cloned().collect();
+    if names.is_empty() {
+        println!("no projects configured, add one with [new project]");
+        return Ok(());
+    }
+    let Some(name) = inquire::Select::new("select project to rename:", names)
+        .with_page_size(page_size())
+        .prompt_skippable()?
+    else {
+        return Ok(());
+    };
+    manage::rename(config, config_file, &name, save)?;
+    Ok(())
+}
+
+/// remove configured projects, found projects are not stored and can't be deleted
+fn delete_projects(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<()> {
+    let names: Vec<String> = config.paths.keys().cloned().collect();
+    if names.is_empty() {
+        println!("no projects configured, add one with [new project]");
+        return Ok(());
+    }
+    let Some(remove) = inquire::MultiSelect::new("select projects to delete:", names)
+        .with_page_size(page_size())
+        .prompt_skippable()?
+    else {
+        return Ok(());
+    };
+    if remove.is_empty() {
+        return Ok(());
+    }
+    let confirmed = inquire::Confirm::new(&format!("delete {}?", remove.join(", ")))
+        .with_default(false)
+        .prompt_skippable()?;
+    if confirmed != Some(true) {
+        return Ok(());
+    }
+    config.paths.retain(|name, _| !remove.contains(name));
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok(())
+}
+
+/// remove configured projects whose path doesn't exist anymore after asking which ones
+fn prune(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<()> {
+    let mut missing = vec![];
+    for (name, project) in &config.paths {
+        // remote paths can't be checked
+        if split_remote(&project.path).is_some() {
+            continue;
+        }
+        match expand_path(&project.path).try_exists() {
+            Ok(true) => (),
+            Ok(false) => missing.push(name.clone()),
+            // e.g. no permission to look inside a parent directory, the path may still exist
+            Err(err) => eprintln!("can't check '{name}' ({}): {err}", project.path),
+        }
+    }
+    if missing.is_empty() {
+        println!("no projects with missing paths");
+        return Ok(());
+    }
+    let Some(remove) = inquire::MultiSelect::new("select projects to remove:", missing)
+        .with_page_size(page_size())
+        .with_all_selected_by_default()
+        .prompt_skippable()?
+    else {
+        return Ok(());
+    };
+    config.paths.retain(|name, _| !remove.contains(name));
+    if save && !remove.is_empty() {
+        save_config(config, config_file)?;
+    }
+    println!("removed {} projects", remove.len());
+    Ok(())
+}
+
+/// add the found projects to `options` and sort them if `sort` is enabled. Projects of dirs with
+/// their own `sort` are added to `separate` instead in the configured order of the dirs, each
+/// sorted on its own
+fn add_options_from_dirs(
+    config: &mut Projects,
+    options: &mut Vec<String>,
+    separate: &mut Vec<Vec<String>>,
+) -> Result<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    if config.dirs.is_some() || config.source_cmds.is_some() {
+        // sources start with the dirs
+        let sorts = config.dirs.iter().flatten().map(|dir| dir.sort);
+        let sorts = sorts.chain(std::iter::repeat(None));
+        for (found, sort) in scan_sources_grouped(config)?.into_iter().zip(sorts) {
+            let mut names = vec![];
+            for (key, path) in found {
+                names.push(key.clone());
+                map.insert(key, path);
+            }
+            match sort {
+                None => options.extend(names),
+                Some(MenuSort::Name) => {
+                    names.sort_by(|a, b| compare_names(config, a, b));
+                    separate.push(names);
+                }
+                Some(MenuSort::Mtime) => {
+                    sort_found_by_mtime(config, &map, &mut names);
+                    separate.push(names);
+                }
+                Some(MenuSort::Recent) => {
+                    sort_by_recent(config, &map, &mut names);
+                    separate.push(names);
+                }
+            }
+        }
+        // without sorting the found projects follow the configured ones in the order of the sources
+        if config.sort.unwrap_or(true) {
+            options.sort_by(|a, b| compare_names(config, a, b));
+        }
+    }
+    Ok(map)
+}
+
+/// projects of all sources in the configured order, names found more than once are kept
+fn scan_sources(config: &Projects) -> Result<Vec<(String, String)>> {
+    Ok(scan_sources_grouped(config)?.concat())
+}
+
+/// projects of each source in the configured order of the sources
+fn scan_sources_grouped(config: &Projects) -> Result<Vec<Vec<(String, String)>>> {
+    // scan all sources in parallel, results are merged in the configured order. The threads own a
+    // copy of the config so a source hanging on a dead mount can be left behind after the timeout
+    let shared = Arc::new(config.clone());
+    let (names, receivers): (Vec<_>, Vec<_>) = source::sources(config)
+        .into_iter()
+        .map(|source| {
+            let (sender, receiver) = mpsc::channel();
+            let config = Arc::clone(&shared);
+            let name = source.name().to_string();
+            std::thread::spawn(move || {
+                let _ = sender.send(source.projects(&config));
+            });
+            (name, receiver)
+        })
+        .unzip();
+    let deadline = config
+        .scan_timeout_ms
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
+    let mut groups = vec![];
+    for (receiver, name) in receivers.into_iter().zip(names) {
+        let result = match deadline {
+            Some(deadline) => {
+                receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => receiver.recv().map_err(RecvTimeoutError::from),
+        };
+        match result {
+            Ok(Ok(found)) => groups.push(found),
+            // e.g. an unmounted or unreadable dir, the other projects are still shown
+            Ok(Err(err)) => {
+                eprintln!("skipping '{name}': {err:#}");
+                groups.push(vec![]);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                eprintln!(
+                    "skipping '{name}': not scanned within {}ms",
+                    config.scan_timeout_ms.unwrap_or_default()
+                );
+                groups.push(vec![]);
+            }
+            // a panic in one source must not take the others with it
+            Err(RecvTimeoutError::Disconnected) => {
+                eprintln!("skipping '{name}': scanning it failed unexpectedly");
+                groups.push(vec![]);
+            }
+        }
+    }
+    if !config.normalize_names.unwrap_or(false) {
+        return Ok(source::disambiguate(config, groups));
+    }
+    // names are made unique across all sources
+    let lengths = groups.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut normalized = source::normalize_names(groups.concat()).into_iter();
+    let groups = lengths
+        .into_iter()
+        .map(|len| normalized.by_ref().take(len).collect())
+        .collect();
+    Ok(source::disambiguate(config, groups))
+}
+
+/// configured projects first in their configured order, then found ones newest first
+fn sort_by_mtime(
+    config: &Projects,
+    dir_paths: &HashMap<String, String>,
+    options: &mut Vec<String>,
+) {
+    let (configured, mut found): (Vec<_>, Vec<_>) = options
+        .drain(..)
+        .partition(|name| config.paths.contains_key(name));
+    sort_found_by_mtime(config, dir_paths, &mut found);
+    *options = config
+        .paths
+        .keys()
+        .filter(|name| configured.contains(name))
+        .cloned()
+        .chain(found)
+        .collect();
+}
+
+/// found projects newest first
+fn sort_found_by_mtime(
+    config: &Projects,
+    dir_paths: &HashMap<String, String>,
+    names: &mut [String],
+) {
+    // entries without a readable mtime go last, equal times are sorted by name and path
+    let modified = names
+        .iter()
+        .map(|name| {
+            let modified = fs::metadata(&dir_paths[name]).and_then(|m| m.modified());
+            (name.clone(), modified.ok())
+        })
+        .collect::<HashMap<_, _>>();
+    names.sort_by(|a, b| {
+        modified[b]
+            .cmp(&modified[a])
+            .then_with(|| compare_names(config, a, b))
+            .then_with(|| dir_paths[a].cmp(&dir_paths[b]))
+    });
+}
+
+/// last opened first, projects that were never opened or without a readable history by name
+fn sort_by_recent(config: &Projects, dir_paths: &HashMap<String, String>, names: &mut [String]) {
+    let history = history::load();
+    let opened = |name: &str| option_path(config, dir_paths, name).and_then(|p| history.get(p));
+    names.sort_by(|a, b| {
+        opened(b)
+            .cmp(&opened(a))
+            .then_with(|| compare_names(config, a, b))
+    });
+}
+
+/// all selectable projects with their paths, configured projects win over found dirs of the same name
+fn list_projects(config: &Projects) -> Result<Vec<(String, String)>> {
+    Ok(menu_order(config, &scan_sources(config)?))
+}
+
+/// configured and found projects in the order of the menu
+fn menu_order(config: &Projects, found: &[(String, String)]) -> Vec<(String, String)> {
+    let mut names: Vec<String> = config.paths.keys().cloned().collect();
+    let mut dir_paths = HashMap::new();
+    if config.dirs.is_some() || config.source_cmds.is_some() {
+        for (name, path) in found {
+            names.push(name.clone());
+            dir_paths.insert(name, path);
+        }
+        if config.sort.unwrap_or(true) {
+            names.sort_by(|a, b| compare_names(config, a, b));
+        }
+    }
+    let mut seen = HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let path = match config.paths.get(&name) {
+                Some(project) => project.path.clone(),
+                None => dir_paths.get(&name)?.to_string(),
+            };
+            Some((name, path))
+        })
+        .collect()
+}
+
+fn update_config(config: &mut Projects, config_file: &PathBuf) -> Result<()> {
+    fill_defaults(config);
+    // only rewrite the file if the content really changed, to keep formatting and order
+    let on_disk: toml::Value = toml::from_str(&fs::read_to_string(config_file)?)?;
+    if toml::Value::try_from(&*config)? != on_disk {
+        save_config(config, config_file)?;
+    }
+    Ok(())
+}
+
+/// report problems of the theme once after loading the config instead of on every menu
+fn validate_theme(config: &mut Projects) {
+    if let Some(theme) = &mut config.theme {
+        theme.validate();
+    }
+}
+
+/// set the defaults of config items added after the config was created
+fn fill_defaults(config: &mut Projects) {
+    if config.sort.is_none() {
+        config.sort = Some(true);
+        sort_config(config);
+    }
+    if config.sort_case_sensitive.is_none() {
+        // sorted before with the byte-wise order
+        config.sort_case_sensitive = Some(false);
+        sort_config(config);
+    }
+    config.dirs.get_or_insert_with(Vec::new);
+    config.exclude_proj_dirs.get_or_insert(false);
+    config.normalize_names.get_or_insert(false);
+    config.resolve_git_root.get_or_insert(false);
+    config.loop_menu.get_or_insert(false);
+    config.esc_exits.get_or_insert(false);
+    config.reuse_window.get_or_insert(false);
+    config.stdin_path.get_or_insert(false);
+    config.confirm_open.get_or_insert(false);
+    config.pre_open_abort.get_or_insert(true);
+    config.init_cmd_abort.get_or_insert(false);
+    config.log_open_output.get_or_insert(false);
+    config.log_sessions.get_or_insert(false);
+    config.show_paths.get_or_insert(false);
+    config.hide_missing.get_or_insert(false);
+    config.detect_lang.get_or_insert(false);
+    config.show_git.get_or_insert(false);
+}
+
+/// commands that are run instead of opening a project with the same name
+const COMMANDS: &[&str] = &[
+    "new",
+    "edit",
+    "manage",
+    "palette",
+    "action",
+    "cd",
+    "shell-init",
+    "which",
+    "open-tag",
+    "serve",
+    "init-config",
+    "last-error",
+    "export",
+    "import",
+    "version",
+    "schema",
+    "prune",
+    "completions",
+    "list",
+    "add",
+    "check",
+];
+
+/// entries at the end of the menu
+const MENU_ACTIONS: [&str; 8] = [
+    "[new project]",
+    "[filter by tag]",
+    "[rename project]",
+    "[delete project]",
+    "[preview readme]",
+    "[new dir]",
+    "[manage dirs]",
+    "[edit]",
+];
+
+/// aliases used by more than one project or as a project name
+fn alias_conflicts(config: &Projects, only: Option<&str>) -> Vec<String> {
+    let mut conflicts = vec![];
+    let mut seen: HashMap<&str, &str> = HashMap::new();
+    // with `only` the conflicts of the other projects are left out
+    let involved = |names: &[&str]| only.is_none_or(|only| names.contains(&only));
+    for (name, project) in &config.paths {
+        for alias in &project.aliases {
+            if config.paths.contains_key(alias) && involved(&[name, alias]) {
+                conflicts.push(format!(
+                    "alias '{alias}' of '{name}' is already used as a project name"
+                ));
+            }
+            if let Some(other) = seen.insert(alias, name) {
+                if involved(&[other, name]) {
+                    conflicts.push(format!(
+                        "alias '{alias}' is used by both '{other}' and '{name}'"
+                    ));
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+/// project names and aliases that are taken by a command or look like a menu entry
+fn reserved_conflicts(config: &Projects, only: Option<&str>) -> Vec<String> {
+    let mut conflicts = vec![];
+    for (name, project) in &config.paths {
+        if only.is_some_and(|only| only != name) {
+            continue;
+        }
+        for token in [name].into_iter().chain(&project.aliases) {
+            let entry = if token == name {
+                format!("project '{name}'")
+            } else {
+                format!("alias '{token}' of '{name}'")
+            };
+            if COMMANDS.contains(&token.as_str()) {
+                conflicts.push(format!("{entry} is shadowed by `wspick {token}`"));
+            } else if MENU_ACTIONS.contains(&token.as_str()) {
+                conflicts.push(format!("{entry} can't be told apart from the menu entry"));
+            }
+        }
+    }
+    conflicts
+}
+
+/// add or rename the project `name` with `change`. Ambiguous names of the project are reported
+/// like by [`check_names`], with `strict` they are an error and `config` stays as it was. Conflicts
+/// between other projects edited into the config by hand are left to the check on start
+fn change_project(
+    config: &mut Projects,
+    name: &str,
+    strict: bool,
+    change: impl FnOnce(&mut Projects),
+) -> Result<()> {
+    let mut changed = config.clone();
+    change(&mut changed);
+    check_names(&changed, Some(name), strict)?;
+    *config = changed;
+    Ok(())
+}
+
+/// warn about names that can't be resolved unambiguously, with `strict` they are an error. With
+/// `only` just the names involving this project are checked
+fn check_names(config: &Projects, only: Option<&str>, strict: bool) -> Result<()> {
+    let mut conflicts = alias_conflicts(config, only);
+    conflicts.extend(reserved_conflicts(config, only));
+    if strict && !conflicts.is_empty() {
+        anyhow::bail!("{}", conflicts.join("\n"));
+    }
+    for conflict in conflicts {
+        eprintln!("{conflict}");
+    }
+    Ok(())
+}
+
+fn save_config(config: &Projects, config_file: &PathBuf) -> Result<()> {
+    let doc = commented_toml(config)?;
+    fs::create_dir_all(config_file.parent().unwrap())?;
+    fs::write(config_file, doc)?;
+    Ok(())
+}
+
+/// the config as TOML with the description of each key as comment above it
+fn commented_toml(config: &Projects) -> Result<String> {
+    let doc = toml::ser::to_string_pretty(config)?;
+    let mut doc_commented = vec![];
+    // add comments
+    let mut in_table = false;
+    for line in doc.lines() {
+        let key = &line[..line.find(' ').unwrap_or(line.len())];
+        // keys inside tables are project settings, only the table headers get comments
+        in_table |= key.starts_with('[');
+        if in_table && !key.starts_with('[') {
+            doc_commented.push(line.to_string());
+            continue;
+        }
+        match key {
+            "open_cmd" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().open_cmd));
+            }
+            "multiplexer" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().multiplexer));
+            }
+            "reuse_window" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().reuse_window));
+            }
+            "pre_open_cmd" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().pre_open_cmd));
+            }
+            "stdin_path" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().stdin_path));
+            }
+            "confirm_open" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().confirm_open));
+            }
+            "pre_open_abort" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().pre_open_abort));
+            }
+            "init_cmd" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().init_cmd));
+            }
+            "init_cmd_abort" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().init_cmd_abort));
+            }
+            "sort" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().sort));
+            }
+            "sort_case_sensitive" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().sort_case_sensitive));
+            }
+            "menu_sort" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().menu_sort));
+            }
+            "exclude_proj_dirs" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().exclude_proj_dirs));
+            }
+            "scan_timeout_ms" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().scan_timeout_ms));
+            }
+            "normalize_names" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().normalize_names));
+            }
+            "resolve_git_root" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().resolve_git_root));
+            }
+            "loop_menu" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().loop_menu));
+            }
+            "esc_exits" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().esc_exits));
+            }
+            "log_open_output" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().log_open_output));
+            }
+            "log_sessions" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().log_sessions));
+            }
+            "show_paths" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().show_paths));
+            }
+            "hide_missing" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().hide_missing));
+            }
+            "detect_lang" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().detect_lang));
+            }
+            "show_git" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().show_git));
+            }
+            // all rules are in one list, only the first gets the comment
+            "[[open_rules]]" if !doc_commented.iter().any(|l| l == line) => {
+                doc_commented.push(format!("# {}", Projects::get_docs().open_rules));
+            }
+            "[theme]" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().theme));
+            }
+            "[paths]" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().paths));
+            }
+            "template_dirs" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().template_dirs));
+            }
+            "source_cmds" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().source_cmds));
+            }
+            "dirs" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().dirs));
+            }
+            "editor" => {
+                doc_commented.push(format!("# {}", Projects::get_docs().editor));
+            }
+            _ => (),
+        }
+        doc_commented.push(line.to_string())
+    }
+    Ok(doc_commented.join("\n"))
+}
+
+/// directory to change into for a project, files are replaced with their parent directory
+fn cd_dir(path: &str) -> PathBuf {
+    let path = expand_path(path);
+    match path.parent() {
+        Some(parent) if path.is_file() => parent.to_path_buf(),
+        _ => path,
+    }
+}
+
+/// ask what the user is working on and append it to the session log
+fn log_session(data_dir: &Path, name: &str) -> Result<()> {
+    let note = inquire::Text::new("what are you working on?")
+        .prompt_skippable()?
+        .unwrap_or_default();
+    fs::create_dir_all(data_dir)?;
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(data_dir.join("sessions.log"))?;
+    writeln!(
+        log,
+        "{}\t{name}\t{note}",
+        format_timestamp(SystemTime::now())
+    )?;
+    Ok(())
+}
+
+/// format a time as UTC timestamp like `2023-06-24T14:05:00Z`
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rest) = (secs / 86400, secs % 86400);
+    // convert days since epoch to a civil date
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// run the pre-open command of the project or the global one and wait for it to finish
+fn pre_open(config: &Projects, name: &str, path: &str) -> Result<()> {
+    let cmd = project_settings(config, name, path)
+        .and_then(|p| p.pre_open_cmd.as_deref())
+        .or(config.pre_open_cmd.as_deref())
+        .unwrap_or_default();
+    let Some((program, args)) = split_cmd(cmd, "pre_open_cmd")? else {
+        return Ok(());
+    };
+    let mut command = Command::new(program::resolve(&program));
+    command.args(args).arg(open_target(config, path));
+    if dry_run(&command) {
+        return Ok(());
+    }
+    let status = command
+        .status()
+        .map_err(|e| not_found_as(e, ErrorCode::CmdNotFound, &program))?;
+    if !status.success() {
+        if config.pre_open_abort.unwrap_or(true) {
+            anyhow::bail!("pre_open_cmd '{cmd}' failed ({status}), not opening '{path}'");
+        }
+        eprintln!("pre_open_cmd '{cmd}' failed ({status})");
+    }
+    Ok(())
+}
+
+/// number of open_cmd output logs to keep
+const MAX_OPEN_LOGS: usize = 20;
+
+/// remove the oldest open_cmd output logs to make room for `new` logs
+fn prune_open_logs(log_dir: &Path, new: usize) -> Result<()> {
+    if !log_dir.try_exists()? {
+        return Ok(());
+    }
+    let mut logs = fs::read_dir(log_dir)?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect::<Vec<_>>();
+    logs.sort();
+    let remove = (logs.len() + new).saturating_sub(MAX_OPEN_LOGS);
+    for (_, log) in logs.into_iter().take(remove) {
+        fs::remove_file(log)?;
+    }
+    Ok(())
+}
+
+/// create a new log file for the output of open_cmd, old logs are removed with [`prune_open_logs`]
+fn open_log(log_dir: &Path, name: &str) -> Result<fs::File> {
+    fs::create_dir_all(log_dir)?;
+    let time = format_timestamp(SystemTime::now()).replace(':', "-");
+    let name = name.replace(['/', '\\'], "_");
+    Ok(fs::File::create(
+        log_dir.join(format!("{name}-{time}.log")),
+    )?)
+}
+
+/// the path that is opened for the selected path, its git root if `resolve_git_root` is enabled
+fn open_target(config: &Projects, path: &str) -> String {
+    let path = &expand(path);
+    if !config.resolve_git_root.unwrap_or(false) {
+        return path.to_string();
+    }
+    // `.git` is a file in worktrees and submodules
+    Path::new(path)
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|root| root.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// the configured project a selection belongs to, `None` for dirs and other paths
+fn project_settings<'a>(config: &'a Projects, name: &str, path: &str) -> Option<&'a Project> {
+    config.paths.get(name).filter(|p| p.path == path)
+}
+
+/// arguments to open a path in an already open window of a supported editor
+fn reuse_window_args(cmd: &str, path: &str) -> Option<Vec<String>> {
+    let program = Path::new(cmd).file_stem()?.to_str()?;
+    match program {
+        "code" | "code-insiders" | "codium" => Some(vec!["--reuse-window".into(), path.into()]),
+        "nvim" => {
+            // only possible from inside a running nvim or with a known server address
+            let server = std::env::var("NVIM")
+                .or_else(|_| std::env::var("NVIM_LISTEN_ADDRESS"))
+                .ok()?;
+            Some(vec![
+                "--server".into(),
+                server,
+                "--remote".into(),
+                path.into(),
+            ])
+        }
+        _ => None,
+    }
+}
+
+/// command running the program with elevated privileges, with `sudo` keeping the environment
+/// or a UAC prompt on windows
+fn elevated(program: &Path, args: &[String]) -> Command {
+    if cfg!(windows) {
+        // powershell strings escape `'` by doubling it, the arguments are quoted for the program
+        let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+        let mut script = format!(
+            "Start-Process -Wait -Verb RunAs -FilePath {}",
+            quote(&program.to_string_lossy())
+        );
+        if !args.is_empty() {
+            let args = args.iter().map(|arg| quote(&format!("\"{arg}\"")));
+            script += &format!(" -ArgumentList {}", args.collect::<Vec<_>>().join(","));
+        }
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command", &script]);
+        command
+    } else {
+        let mut command = Command::new("sudo");
+        command.arg("--preserve-env").arg(program).args(args);
+        command
+    }
+}
+
+/// open the path with the configured open_cmd, output is logged to `log_dir` if enabled. Old logs
+/// have to be removed before with [`prune_open_logs`]
+fn open_project(
+    config: &Projects,
+    name: &str,
+    path: &str,
+    print: bool,
+    log_dir: &Path,
+) -> Result<()> {
+    let target = open_target(config, path);
+    if print {
+        println!("{target}");
+    } else {
+        let multiplexer = config.multiplexer.as_deref().unwrap_or_default();
+        if !multiplexer.is_empty()
+            && split_remote(&target).is_none()
+            && multiplexer::open(multiplexer, name, &target)?
+        {
+            return init(config, name, path, &target);
+        }
+        let remote = split_remote(&target);
+        let settings = project_settings(config, name, path);
+        let own_cmd = settings
+            .and_then(|p| p.open_cmd.as_deref())
+            .filter(|cmd| !cmd.is_empty());
+        let rule = remote
+            .is_none()
+            .then(|| open_rule(config, &target))
+            .flatten()
+            .filter(|_| own_cmd.is_none());
+        let (cmd, args) = match (own_cmd, rule) {
+            (Some(own_cmd), _) => split_cmd(own_cmd, "open_cmd of the project")?,
+            (None, Some(rule)) => split_cmd(&rule.cmd, "open_rules")?,
+            (None, None) => split_cmd(&config.open_cmd, "open_cmd")?,
+        }
+        .ok_or_else(no_open_cmd)?;
+        let recent = remote.is_none()
+            && (RECENT_FILE.load(Ordering::Relaxed)
+                || settings.and_then(|p| p.recent_file).unwrap_or(false));
+        let target = settings
+            .filter(|_| remote.is_none())
+            .and_then(|p| workspace(p, path))
+            .or_else(|| {
+                // an empty project opens the directory
+                let file = recent.then(|| recent::most_recent(&target)).flatten()?;
+                Some(file.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| target.clone());
+        let cmd = cmd.as_str();
+        let reuse = settings
+            .and_then(|p| p.reuse_window)
+            .or(config.reuse_window)
+            .unwrap_or(false);
+        let stdin_path = config.stdin_path.unwrap_or(false);
+        let elevate =
+            ELEVATE.load(Ordering::Relaxed) || settings.and_then(|p| p.elevate).unwrap_or(false);
+        // one log for all calls of a project with several paths
+        let log = config
+            .log_open_output
+            .unwrap_or(false)
+            .then(|| open_log(log_dir, name))
+            .transpose()?;
+        let run = |args: Vec<String>, target: &str| -> Result<()> {
+            let mut command = if elevate {
+                elevated(&program::resolve(cmd), &args)
+            } else {
+                let mut command = Command::new(program::resolve(cmd));
+                command.args(args);
+                command
+            };
+            if let Some(log) = &log {
+                command.stdout(log.try_clone()?).stderr(log.try_clone()?);
+            }
+            if stdin_path {
+                command.stdin(Stdio::piped());
+            }
+            if dry_run(&command) {
+                return Ok(());
+            }
+            if config.confirm_open.unwrap_or(false) {
+                let confirmed = inquire::Confirm::new(&format!("run {}?", command_line(&command)))
+                    .with_default(true)
+                    .prompt()?;
+                if !confirmed {
+                    anyhow::bail!("not opening '{target}'");
+                }
+            }
+            let mut child = command.spawn().map_err(|e| {
+                // only open_cmd can be replaced when asked for a different one
+                if elevate {
+                    let wrapper = command.get_program().to_string_lossy().to_string();
+                    not_found_as(e, ErrorCode::CmdNotFound, &wrapper)
+                } else if rule.is_some() || own_cmd.is_some() {
+                    not_found_as(e, ErrorCode::CmdNotFound, cmd)
+                } else if e.kind() == std::io::ErrorKind::NotFound {
+                    OpenCmdNotFound(cmd.to_string()).into()
+                } else {
+                    anyhow::Error::from(e)
+                }
+            })?;
+            if let Some(mut stdin) = child.stdin.take() {
+                // commands that exit without reading their input are fine
+                match writeln!(stdin, "{target}") {
+                    Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => {
+                        return Err(err.into())
+                    }
+                    _ => (),
+                }
+            }
+            let status = child.wait()?;
+            if !status.success() {
+                return Err(CommandFailed {
+                    cmd: cmd.to_string(),
+                    status,
+                }
+                .into());
+            }
+            Ok(())
+        };
+        let more = settings
+            .filter(|_| remote.is_none())
+            .map(|p| more_paths(config, p))
+            .unwrap_or_default();
+        if more.is_empty() || (!stdin_path && opens_many(cmd)) {
+            let built = build_args(cmd, &args, &target, &more, remote, reuse, stdin_path);
+            run(built, &target)?;
+        } else {
+            // one call for every path
+            for target in [&target].into_iter().chain(&more) {
+                let built = build_args(cmd, &args, target, &[], remote, reuse, stdin_path);
+                run(built, target)?;
+            }
+        }
+        if remote.is_none() {
+            init(config, name, path, &open_target(config, path))?;
+        }
+    }
+    Ok(())
+}
+
+/// arguments of open_cmd to open `target`, `more` are opened in the same call. Placeholders in
+/// `args` are replaced, without one the path is appended unless it is passed on stdin
+fn build_args(
+    cmd: &str,
+    args: &[String],
+    target: &str,
+    more: &[String],
+    remote: Option<(&str, &str)>,
+    reuse: bool,
+    stdin_path: bool,
+) -> Vec<String> {
+    let (host, remote_path) = remote.unwrap_or(("", target));
+    let placeholders = [
+        ("{workspace}", target),
+        // short form, e.g. `sh -c 'cd {} && nvim'`
+        ("{}", target),
+        ("{host}", host),
+        ("{remote_path}", remote_path),
+    ];
+    // with a placeholder the arguments are complete, otherwise the path is appended
+    let templated = args
+        .iter()
+        .any(|arg| placeholders.iter().any(|(key, _)| arg.contains(key)));
+    let mut built = vec![];
+    for arg in args {
+        if arg == "{workspace}" || arg == "{}" {
+            built.push(target.to_string());
+            built.extend(more.iter().cloned());
+            continue;
+        }
+        built.push(
+            placeholders
+                .iter()
+                .fold(arg.clone(), |arg, (key, value)| arg.replace(key, value)),
+        );
+    }
+    if !stdin_path && !templated {
+        match reuse.then(|| reuse_window_args(cmd, target)).flatten() {
+            Some(reuse_args) => built.extend(reuse_args),
+            None => built.push(target.to_string()),
+        };
+        built.extend(more.iter().cloned());
+    }
+    built
+}
+
+/// the existing further paths of a project, missing ones are skipped with a warning
+fn more_paths(config: &Projects, project: &Project) -> Vec<String> {
+    project
+        .more_paths
+        .iter()
+        .filter(|path| {
+            let exists = expand_path(path).exists();
+            if !exists {
+                eprintln!("'{path}' not found, not opening it");
+            }
+            exists
+        })
+        .map(|path| open_target(config, path))
+        .collect()
+}
+
+/// editors that open all folders given as arguments in one window
+fn opens_many(cmd: &str) -> bool {
+    let program = Path::new(cmd).file_stem().and_then(|p| p.to_str());
+    matches!(
+        program,
+        Some("code" | "code-insiders" | "codium" | "subl" | "zed")
+    )
+}
+
+/// the first rule whose marker exists in the directory
+fn open_rule<'a>(config: &'a Projects, dir: &str) -> Option<&'a OpenRule> {
+    config
+        .open_rules
+        .iter()
+        .flatten()
+        .find(|rule| Path::new(dir).join(&rule.marker).exists())
+}
+
+/// run the init command of the project or the global one inside the opened directory
+fn init(config: &Projects, name: &str, path: &str, dir: &str) -> Result<()> {
+    let cmd = project_settings(config, name, path)
+        .and_then(|p| p.init_cmd.as_deref())
+        .or(config.init_cmd.as_deref())
+        .unwrap_or_default();
+    let Some((program, args)) = split_cmd(cmd, "init_cmd")? else {
+        return Ok(());
+    };
+    let placeholders = [("{name}", name), ("{path}", dir)];
+    let mut command = Command::new(program::resolve(&program));
+    command.current_dir(dir).args(args.iter().map(|arg| {
+        placeholders
+            .iter()
+            .fold(arg.clone(), |arg, (key, value)| arg.replace(key, value))
+    }));
+    if dry_run(&command) {
+        return Ok(());
+    }
+    let status = command
+        .status()
+        .map_err(|e| not_found_as(e, ErrorCode::CmdNotFound, &program))?;
+    if !status.success() {
+        if config.init_cmd_abort.unwrap_or(false) {
+            return Err(CommandFailed {
+                cmd: cmd.to_string(),
+                status,
+            }
+            .into());
+        }
+        eprintln!("init_cmd '{cmd}' failed ({status})");
+    }
+    Ok(())
+}
+
+/// set by `--no-exec`, no process is started at all while it is set
+static NO_EXEC: AtomicBool = AtomicBool::new(false);
+
+/// set by `--recent-file`, projects are opened at their most recently modified file
+static RECENT_FILE: AtomicBool = AtomicBool::new(false);
+
+/// set by `--sudo`, open_cmd is run with elevated privileges
+static ELEVATE: AtomicBool = AtomicBool::new(false);
+
+/// set by `--strict`, ambiguous names of added or renamed projects or in a reloaded config are an
+/// error
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// set by `--verbose`, skipped entries of `dirs` are reported on stderr
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// print the command instead of running it if `--no-exec` is set, returns if it was skipped
+fn dry_run(command: &Command) -> bool {
+    if !NO_EXEC.load(Ordering::Relaxed) {
+        return false;
+    }
+    println!("would run: {}", command_line(command));
+    true
+}
+
+/// the command as it would be typed in a shell
+fn command_line(command: &Command) -> String {
+    let args = [command.get_program()]
+        .into_iter()
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    let mut line =
+        shlex::try_join(args.iter().map(String::as_str)).unwrap_or_else(|_| args.join(" "));
+    if let Some(dir) = command.get_current_dir() {
+        let dir = dir.to_string_lossy();
+        line = format!(
+            "cd {} && {line}",
+            shlex::try_quote(&dir).unwrap_or(dir.clone())
+        );
+    }
+    line
+}
+
+/// program and arguments of a configured command, quoted like in a shell, `None` if it is empty
+fn split_cmd(cmd: &str, key: &str) -> Result<Option<(String, Vec<String>)>> {
+    let words = if cfg!(windows) {
+        split_windows(cmd)
+    } else {
+        shlex::split(cmd)
+    };
+    let mut args = words
+        .ok_or_else(|| anyhow::anyhow!("invalid quoting in {key} '{cmd}'"))?
+        .into_iter();
+    Ok(args.next().map(|program| (program, args.collect())))
+}
+
+/// split like `cmd.exe`, backslashes are path separators and only `\"` is an escaped quote.
+/// `None` if a quote is not closed
+fn split_windows(cmd: &str) -> Option<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    let mut chars = cmd.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'"') => {
+                chars.next();
+                word.get_or_insert_with(String::new).push('"');
+            }
+            // `""` is an empty argument
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    words.extend(word);
+    Some(words)
+}
+
+/// host and path of a remote `host:/path` entry, single letters are windows drives like `C:\`
+fn split_remote(path: &str) -> Option<(&str, &str)> {
+    let (host, remote_path) = path.split_once(':')?;
+    if host.chars().count() < 2 || host.contains(['/', '\\']) || remote_path.is_empty() {
+        return None;
+    }
+    Some((host, remote_path))
+}
+
+/// the workspace file of a project inside `path`, `None` if it has none or it is missing
+fn workspace(project: &Project, path: &str) -> Option<String> {
+    let file = expand_path(path).join(project.workspace_file.as_ref()?);
+    if !file.exists() {
+        eprintln!(
+            "workspace file '{}' not found, opening '{path}'",
+            file.display()
+        );
+        return None;
+    }
+    Some(file.to_string_lossy().to_string())
+}
+
+/// number of projects [open-tag] opens without asking first
+const MAX_OPEN_UNCONFIRMED: usize = 5;
+
+/// open all configured projects with the tag at once, failures are reported after all were tried
+fn open_tag(config: &Projects, tag: &str, print: bool, log_dir: &Path) -> Result<()> {
+    let projects = config
+        .paths
+        .iter()
+        .filter(|(_, p)| p.tags.iter().any(|t| t == tag))
+        .collect::<Vec<_>>();
+    if projects.is_empty() {
+        anyhow::bail!("no project is tagged with '{tag}'");
+    }
+    if print {
+        for (_, project) in projects {
+            println!("{}", open_target(config, &project.path));
+        }
+        return Ok(());
+    }
+    // the projects are opened in parallel, so confirm_open asks once for all of them
+    let confirm = config.confirm_open.unwrap_or(false);
+    let many = projects.len() > MAX_OPEN_UNCONFIRMED;
+    if many || (confirm && !NO_EXEC.load(Ordering::Relaxed)) {
+        let names = projects.iter().map(|(name, _)| name.as_str());
+        let confirmed = inquire::Confirm::new(&format!(
+            "open {} projects ({})?",
+            projects.len(),
+            names.collect::<Vec<_>>().join(", ")
+        ))
+        .with_default(!many)
+        .prompt()?;
+        if !confirmed {
+            return Ok(());
+        }
+    }
+    let mut config = config.clone();
+    config.confirm_open = Some(false);
+    let config = &config;
+    if config.log_open_output.unwrap_or(false) {
+        prune_open_logs(log_dir, projects.len())?;
+    }
+    let errors = std::thread::scope(|s| {
+        let handles = projects
+            .iter()
+            .map(|(name, project)| {
+                s.spawn(|| {
+                    pre_open(config, name, &project.path)?;
+                    open_project(config, name, &project.path, false, log_dir)
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .zip(&projects)
+            .filter_map(|(handle, (name, _))| match handle.join() {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(format!("{name}: {err:#}")),
+                Err(_) => Some(format!("{name}: panicked")),
+            })
+            .collect::<Vec<_>>()
+    });
+    for err in &errors {
+        eprintln!("{err}");
+    }
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "failed to open {} of {} projects",
+            errors.len(),
+            projects.len()
+        );
+    }
+    Ok(())
+}
+
+/// ask for a different open_cmd after the configured one wasn't found, returns if one was given
+fn replace_open_cmd(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<bool> {
+    const THIS_RUN: &str = "use a different command for this run";
+    const IN_CONFIG: &str = "change open_cmd in the config";
+    let Some(choice) = inquire::Select::new("open with:", vec![THIS_RUN, IN_CONFIG])
+        .with_help_message("esc to abort")
+        .prompt_skippable()?
+    else {
+        return Ok(false);
+    };
+    let Some(cmd) = inquire::Text::new("open_cmd:")
+        .with_initial_value(&config.open_cmd)
+        .prompt_skippable()?
+    else {
+        return Ok(false);
+    };
+    config.open_cmd = cmd;
+    if choice == IN_CONFIG && save {
+        save_config(config, config_file)?;
+    }
+    Ok(true)
+}
+
+/// error for opening without `open_cmd`, the empty default of new configs
+fn no_open_cmd() -> anyhow::Error {
+    CodedError::new(
+        ErrorCode::CmdNotFound,
+        "no open_cmd configured, set one with `wspick edit` or use --print to print the path",
+    )
+    .into()
+}
+
+/// tag a `NotFound` io error with a more specific code
+fn not_found_as(err: std::io::Error, code: ErrorCode, name: &str) -> anyhow::Error {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        CodedError::new(code, format!("'{name}' not found")).into()
+    } else {
+        err.into()
+    }
+}
+
+#[derive(Clone)]
+struct FileValidator;
+impl StringValidator for FileValidator {
+    fn validate(
+        &self,
+        input: &str,
+    ) -> std::result::Result<inquire::validator::Validation, inquire::CustomUserError> {
+        match expand_path(input).try_exists() {
+            Ok(val) => {
+                // remote paths can't be checked
+                if val || split_remote(input).is_some() {
+                    Ok(Validation::Valid)
+                } else {
+                    Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                        "path '{input}' does not exist"
+                    ))))
+                }
+            }
+            Err(e) => Err(CustomUserError::from(e)),
+        }
+    }
+}
+
+/// rejects empty names and names of other projects
+#[derive(Clone)]
+struct NameValidator {
+    taken: Vec<String>,
+}
+impl NameValidator {
+    /// `current` is the name of the project being renamed and stays allowed
+    fn new(config: &Projects, current: &str) -> Self {
+        let taken = config.paths.keys().filter(|k| *k != current).cloned();
+        Self {
+            taken: taken.collect(),
+        }
+    }
+}
+impl StringValidator for NameValidator {
+    fn validate(
+        &self,
+        input: &str,
+    ) -> std::result::Result<inquire::validator::Validation, inquire::CustomUserError> {
+        if input.is_empty() {
+            Ok(Validation::Invalid(ErrorMessage::Custom(
+                "name can't be empty".into(),
+            )))
+        } else if self.taken.iter().any(|t| t == input) {
+            Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                "project '{input}' already exists"
+            ))))
+        } else {
+            Ok(Validation::Valid)
+        }
+    }
 }
 
 fn new_project(
     config: &mut Projects,
     config_file: &PathBuf,
+    name: Option<String>,
     path: Option<String>,
-) -> Result<String> {
-    let name = inquire::Text::new("project name:").prompt()?;
+    save: bool,
+) -> Result<(String, String)> {
+    let name = inquire::Text::new("project name:")
+        .with_initial_value(name.as_deref().unwrap_or_default())
+        .prompt()?;
     let path = match path {
         Some(p) => p,
-        None => inquire::Text::new("project path:")
-            .with_validator(FileValidator)
-            .prompt()?,
+        None => match template::create_project(config, &name)? {
+            Some(p) => p,
+            None => inquire::Text::new("project path:")
+                .with_validator(FileValidator)
+                .prompt()?,
+        },
     };
     // store adjusted config
-    config.paths.insert(name, path.clone());
+    change_project(config, &name, STRICT.load(Ordering::Relaxed), |config| {
+        config.paths.insert(name.clone(), path.clone().into());
+        sort_config(config);
+    })?;
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok((name, path))
+}
+
+/// add an existing path without choosing a template, named after the directory if `name` isn't
+/// given. Local paths are stored absolute
+fn add_project(
+    config: &mut Projects,
+    config_file: &PathBuf,
+    path: &str,
+    name: Option<String>,
+    save: bool,
+) -> Result<(String, String)> {
+    if let Validation::Invalid(message) = FileValidator
+        .validate(path)
+        .map_err(|e| anyhow::anyhow!(e))?
+    {
+        let message = match message {
+            ErrorMessage::Custom(message) => message,
+            ErrorMessage::Default => format!("'{path}' is not a valid path"),
+        };
+        return Err(CodedError::new(ErrorCode::PathMissing, message).into());
+    }
+    let path = match split_remote(path) {
+        Some(_) => path.to_string(),
+        None => fs::canonicalize(expand_path(path))?
+            .to_string_lossy()
+            .to_string(),
+    };
+    let mut name = match name {
+        Some(name) => name,
+        None => {
+            let dir_name = Path::new(&path).file_name().unwrap_or_default();
+            // taken names are asked about below
+            inquire::Text::new("project name:")
+                .with_initial_value(&dir_name.to_string_lossy())
+                .with_validator(|input: &str| {
+                    Ok(match input.is_empty() {
+                        true => Validation::Invalid("name can't be empty".into()),
+                        false => Validation::Valid,
+                    })
+                })
+                .prompt()?
+        }
+    };
+    const OVERWRITE: &str = "overwrite it";
+    const OTHER_NAME: &str = "choose another name";
+    if config.paths.get(&name).is_some_and(|p| p.path != path) {
+        let choice = inquire::Select::new(
+            &format!("'{name}' already exists:"),
+            vec![OVERWRITE, OTHER_NAME],
+        )
+        .prompt()?;
+        if choice == OTHER_NAME {
+            name = inquire::Text::new("project name:")
+                .with_validator(NameValidator::new(config, ""))
+                .prompt()?;
+        }
+    }
+    // keep the other settings when only the path changes
+    let mut project = config.paths.get(&name).cloned().unwrap_or_default();
+    project.path = path.clone();
+    change_project(config, &name, STRICT.load(Ordering::Relaxed), |config| {
+        config.paths.insert(name.clone(), project);
+        sort_config(config);
+    })?;
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok((name, path))
+}
+
+/// create a missing project directory if confirmed or `create` is set
+fn create_missing_dir(path: &str, create: bool) -> Result<()> {
+    if expand_path(path).try_exists()? {
+        return Ok(());
+    }
+    let confirmed = create
+        || inquire::Confirm::new(&format!("'{path}' does not exist, create it?"))
+            .with_default(false)
+            .prompt()?;
+    if !confirmed {
+        return Err(
+            CodedError::new(ErrorCode::PathMissing, format!("'{path}' does not exist")).into(),
+        );
+    }
+    fs::create_dir_all(expand_path(path))?;
+    Ok(())
+}
+
+/// the project a name from the command line refers to. Checked in order are names in `paths`,
+/// aliases, names of found projects, the position in the menu starting at 1 and existing paths.
+/// `found` are the projects of all sources if they were already scanned
+fn resolve(
+    config: &Projects,
+    found: Option<&[(String, String)]>,
+    token: &str,
+) -> Result<(String, String)> {
+    if let Some(project) = config.paths.get(token) {
+        return Ok((token.to_string(), project.path.clone()));
+    }
+    let aliased = config
+        .paths
+        .iter()
+        .filter(|(_, p)| p.aliases.iter().any(|a| a == token))
+        .map(|(name, p)| (name.clone(), p.path.clone()));
+    if let Some(project) = unambiguous(token, aliased.collect())? {
+        return Ok(project);
+    }
+    let scanned;
+    let found = match found {
+        Some(found) => found,
+        None => {
+            scanned = scan_sources(config)?;
+            &scanned
+        }
+    };
+    let named = found.iter().filter(|(name, _)| name == token).cloned();
+    if let Some(project) = unambiguous(token, named.collect())? {
+        return Ok(project);
+    }
+    let projects = menu_order(config, found);
+    let position = token.parse::<usize>().ok().and_then(|i| i.checked_sub(1));
+    if let Some(project) = position.and_then(|i| projects.get(i)) {
+        return Ok(project.clone());
+    }
+    if split_remote(token).is_some() || Path::new(token).try_exists()? {
+        let dir_name = Path::new(token)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        return Ok((dir_name.unwrap_or_else(|| token.to_string()), token.into()));
+    }
+    let lower = token.to_lowercase();
+    let similar = projects
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| name.to_lowercase().contains(&lower))
+        .take(3)
+        .collect::<Vec<_>>();
+    let mut message = format!("unknown project '{token}'");
+    if !similar.is_empty() {
+        message += &format!(", did you mean {}?", similar.join(", "));
+    }
+    Err(CodedError::new(ErrorCode::PathMissing, message).into())
+}
+
+/// the project if all matches are the same, an error listing them otherwise
+fn unambiguous(
+    token: &str,
+    mut matches: Vec<(String, String)>,
+) -> Result<Option<(String, String)>> {
+    matches.sort();
+    matches.dedup();
+    if matches.len() > 1 {
+        let list = matches
+            .iter()
+            .map(|(name, path)| format!("{name} ({path})"))
+            .collect::<Vec<_>>();
+        anyhow::bail!(
+            "'{token}' is ambiguous, did you mean {}?",
+            list.join(" or ")
+        );
+    }
+    Ok(matches.pop())
+}
+
+/// change the path of a single configured project without opening the editor
+fn edit_path(config: &mut Projects, config_file: &PathBuf, name: &str, save: bool) -> Result<()> {
+    let key = match config.paths.get_key_value(name) {
+        Some((key, _)) => key.clone(),
+        None => match config
+            .paths
+            .iter()
+            .find(|(_, p)| p.aliases.iter().any(|a| a == name))
+        {
+            Some((key, _)) => key.clone(),
+            None => {
+                let dir_paths = add_options_from_dirs(config, &mut vec![], &mut vec![])?;
+                if dir_paths.contains_key(name) {
+                    anyhow::bail!(
+                        "'{name}' is found in a scanned directory, only projects in [paths] can be edited"
+                    );
+                }
+                anyhow::bail!("unknown project '{name}'");
+            }
+        },
+    };
+    let project = config.paths.get_mut(&key).unwrap();
+    project.path = inquire::Text::new(&format!("new path for '{key}':"))
+        .with_initial_value(&project.path)
+        .with_validator(FileValidator)
+        .prompt()?;
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok(())
+}
+
+/// rename a configured project while keeping its position
+fn rename_project(config: &mut Projects, name: &str, new_name: String) {
+    if let Some((index, _, project)) = config.paths.shift_remove_full(name) {
+        config.paths.shift_insert(index, new_name, project);
+    }
     sort_config(config);
-    save_config(config, config_file)?;
-    Ok(path)
 }
 
+/// sort the configured projects by name if `sort` is enabled
 fn sort_config(config: &mut Projects) {
     if config.sort.unwrap_or(false) {
         let mut new_paths = IndexMap::with_capacity(config.paths.len());
         let mut keys = config.paths.keys().cloned().collect::<Vec<String>>();
-        keys.sort();
+        keys.sort_by(|a, b| compare_names(config, a, b));
         for k in keys {
             let val = config.paths.swap_remove(&k).unwrap();
             new_paths.insert(k, val);
@@ -353,17 +3052,463 @@ fn sort_config(config: &mut Projects) {
     }
 }
 
+/// natural order of two names with the case handled as configured in `sort_case_sensitive`
+fn compare_names(config: &Projects, a: &str, b: &str) -> std::cmp::Ordering {
+    natural::compare(a, b, !config.sort_case_sensitive.unwrap_or(false))
+}
+
 fn edit_project(config: &mut Projects, config_file: &PathBuf) -> Result<()> {
-    Command::new(&config.editor)
-        .arg(config_file)
-        .spawn()?
+    edit_config(config, config_file, None, ask_load_failed)
+}
+
+/// ask whether to load the config after the editor failed, the file might only be partly written
+/// after a crash or `:cq`
+fn ask_load_failed(status: ExitStatus) -> Result<bool> {
+    Ok(inquire::Confirm::new(&format!(
+        "the editor exited with {status}, load the config anyway?"
+    ))
+    .with_default(false)
+    .prompt()?)
+}
+
+/// open the config in the editor and reload it. The edited file only replaces `config` if it is
+/// valid, otherwise it can be edited again or the previous config is kept. If the editor fails
+/// the config is only loaded if `load_failed` agrees
+fn edit_config(
+    config: &mut Projects,
+    config_file: &PathBuf,
+    mut position: Option<(usize, usize)>,
+    load_failed: impl Fn(ExitStatus) -> Result<bool>,
+) -> Result<()> {
+    if !config_file.try_exists()? {
+        save_config(config, config_file)?;
+    }
+    loop {
+        let Some(status) = run_editor(&config.editor, config_file, position)? else {
+            return Ok(());
+        };
+        if !status.success() && !load_failed(status)? {
+            eprintln!("keeping the previous config");
+            return Ok(());
+        }
+        let content = fs::read_to_string(config_file)?;
+        let err = match toml::from_str(&content) {
+            Ok(edited) => {
+                *config = edited;
+                validate_theme(config);
+                // open_cmd or editor might have changed
+                program::clear_cache();
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+        const EDIT_AGAIN: &str = "edit again";
+        const KEEP: &str = "keep the previous config";
+        let choice = inquire::Select::new(
+            &format!(
+                "config file '{}' is invalid: {err}\n\nwhat do you want to do?",
+                config_file.display()
+            ),
+            vec![EDIT_AGAIN, KEEP],
+        )
+        .prompt_skippable()?;
+        if choice != Some(EDIT_AGAIN) {
+            eprintln!(
+                "changes to '{}' are not applied, it is overwritten when the config is saved",
+                config_file.display()
+            );
+            return Ok(());
+        }
+        position = error_position(&content, &err);
+    }
+}
+
+/// open `file` in the editor, at the line and column of `position` if the editor supports it.
+/// Returns the exit status of the editor, `None` if it wasn't started because of `--no-exec`
+fn run_editor(
+    editor: &str,
+    file: &Path,
+    position: Option<(usize, usize)>,
+) -> Result<Option<ExitStatus>> {
+    let goto = position.and_then(|(line, column)| goto_line_args(editor, file, line, column));
+    let (program, mut command) = match goto {
+        Some(mut args) => {
+            let program = args.remove(0);
+            let mut command = Command::new(program::resolve(&program));
+            command.args(args);
+            (program, command)
+        }
+        None => {
+            // `editor` can contain arguments like `code --wait`, the file is passed last
+            let (program, args) = split_cmd(editor, "editor")?
+                .ok_or_else(|| anyhow::anyhow!("no editor configured"))?;
+            let mut command = Command::new(program::resolve(&program));
+            command.args(args).arg(file);
+            (program, command)
+        }
+    };
+    if dry_run(&command) {
+        return Ok(None);
+    }
+    let status = command
+        .spawn()
+        .map_err(|e| not_found_as(e, ErrorCode::CmdNotFound, &program))?
         .wait()?;
-    let new_config = load_config(config_file)?;
-    config.paths = new_config.paths;
-    config.editor = new_config.editor;
-    config.open_cmd = new_config.open_cmd;
-    config.sort = new_config.sort;
-    config.dirs = new_config.dirs;
-    config.exclude_proj_dirs = new_config.exclude_proj_dirs;
-    Ok(())
+    Ok(Some(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_dirs, path};
+
+    fn dir(root: &Path, dir: &str) -> SearchDir {
+        SearchDir::from(path(root, dir))
+    }
+
+    #[test]
+    fn parallel_scan_matches_sequential() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(
+            root,
+            &[
+                "a/app", "a/lib", "a/utils", "b/api", "b/utils", "b/web", "c/one",
+            ],
+        );
+        let mut config = Projects::new();
+        config.dirs = Some(vec![dir(root, "a"), dir(root, "b"), dir(root, "c")]);
+        let sequential = source::sources(&config)
+            .iter()
+            .map(|source| source.projects(&config).unwrap())
+            .collect();
+        assert_eq!(
+            scan_sources_grouped(&config).unwrap(),
+            source::disambiguate(&config, sequential)
+        );
+    }
+
+    #[test]
+    fn sort_config_orders_equal_names_uppercase_first() {
+        let mut config = Projects::new();
+        for name in ["foo", "bar", "Foo"] {
+            config.paths.insert(name.into(), "/tmp".to_string().into());
+        }
+        sort_config(&mut config);
+        assert_eq!(
+            config.paths.keys().collect::<Vec<_>>(),
+            ["bar", "Foo", "foo"]
+        );
+    }
+
+    #[test]
+    fn unsorted_keeps_configured_order() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["code/beta", "code/alpha"]);
+        let mut config = Projects::new();
+        config.sort = Some(false);
+        config.dirs = Some(vec![dir(root, "code")]);
+        for name in ["zeta", "mid", "apple"] {
+            config.paths.insert(name.into(), "/tmp".to_string().into());
+        }
+        let expected = ["zeta", "mid", "apple", "alpha", "beta"];
+        let listed = list_projects(&config).unwrap();
+        let listed = listed.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(listed, expected);
+        let menu = Menu::new(&config, &Flags::parse_from(["wspick"]).menu);
+        let (entries, _) = menu_entries(&mut config, &menu).unwrap();
+        assert_eq!(entries[..expected.len()], expected);
+    }
+
+    /// arguments of open_cmd `cmd` with `args` to open the local path `target`
+    fn open_args(cmd: &str, args: &[&str], target: &str, more: &[&str]) -> Vec<String> {
+        let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let more = more.iter().map(|path| path.to_string()).collect::<Vec<_>>();
+        build_args(cmd, &args, target, &more, None, false, false)
+    }
+
+    #[test]
+    fn build_args_appends_path() {
+        assert_eq!(
+            open_args("code", &["--new-window"], "/home/me/my project", &[]),
+            ["--new-window", "/home/me/my project"]
+        );
+    }
+
+    #[test]
+    fn build_args_keeps_quoted_arguments() {
+        // double quotes are split the same on every platform
+        let (cmd, args) = split_cmd(r#"alacritty --title "my editor" -e nvim"#, "open_cmd")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            build_args(&cmd, &args, "/my project", &[], None, false, false),
+            ["--title", "my editor", "-e", "nvim", "/my project"]
+        );
+    }
+
+    #[test]
+    fn build_args_placeholder() {
+        // `sh -c 'cd {} && nvim'`
+        assert_eq!(
+            open_args("sh", &["-c", "cd {} && nvim"], "/p", &[]),
+            ["-c", "cd /p && nvim"]
+        );
+        assert_eq!(
+            open_args("code", &["{}", "--wait"], "/p", &["/q"]),
+            ["/p", "/q", "--wait"]
+        );
+        assert_eq!(open_args("code", &["{workspace}"], "/p", &[]), ["/p"]);
+    }
+
+    #[test]
+    fn build_args_stdin_path() {
+        assert!(build_args("nvim", &[], "/p", &[], None, false, true).is_empty());
+    }
+
+    #[test]
+    fn command_line_quotes_arguments() {
+        let mut command = Command::new("code");
+        command.args(["--wait", "/home/me/my project"]);
+        assert_eq!(command_line(&command), "code --wait '/home/me/my project'");
+    }
+
+    #[test]
+    fn command_line_of_resolved_open_cmd() {
+        let args = ["-c".to_string(), "cd {} && nvim".to_string()];
+        let mut command = Command::new("sh");
+        command.args(build_args("sh", &args, "/p", &[], None, false, false));
+        assert_eq!(command_line(&command), "sh -c 'cd /p && nvim'");
+    }
+
+    #[test]
+    fn command_line_with_directory() {
+        let mut command = Command::new("cargo");
+        command.arg("build").current_dir("/home/me/my project");
+        assert_eq!(
+            command_line(&command),
+            "cd '/home/me/my project' && cargo build"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_survive_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("wspick.toml");
+        let content = "open_cmd = \"code\"\neditor = \"vi\"\nfoo = 1\n[paths]\n";
+        fs::write(&file, content).unwrap();
+        let config = load_config(&file).unwrap();
+        save_config(&config, &file).unwrap();
+        let saved: toml::Value = toml::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(saved.get("foo"), Some(&toml::Value::Integer(1)));
+    }
+
+    /// `false` is a program on unix only
+    #[cfg(unix)]
+    #[test]
+    fn failed_editor_keeps_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("wspick.toml");
+        let mut config = Projects::new();
+        config.editor = "false".into();
+        save_config(&config, &file).unwrap();
+        // the file changes while the editor runs
+        fs::write(&file, "open_cmd = \"code\"\neditor = \"vi\"\n[paths]\n").unwrap();
+        let asked = std::cell::Cell::new(None);
+        let load_failed = |status: ExitStatus| {
+            asked.set(Some(status.success()));
+            Ok(false)
+        };
+        edit_config(&mut config, &file, None, load_failed).unwrap();
+        assert_eq!(asked.get(), Some(false));
+        assert_eq!(config.editor, "false");
+        // loaded anyway when agreed to
+        edit_config(&mut config, &file, None, |_| Ok(true)).unwrap();
+        assert_eq!(config.editor, "vi");
+    }
+
+    /// a project at `/tmp` with `aliases`
+    fn project(aliases: &[&str]) -> Project {
+        let mut project = Project::from("/tmp".to_string());
+        project.aliases = aliases.iter().map(|alias| alias.to_string()).collect();
+        project
+    }
+
+    #[test]
+    fn rejected_change_keeps_config() {
+        let mut config = Projects::new();
+        config.paths.insert("api".into(), project(&["x"]));
+        config.paths.insert("web".into(), project(&[]));
+        let before = config.paths.clone();
+        let add = change_project(&mut config, "x", true, |config| {
+            config.paths.insert("x".into(), project(&[]));
+        });
+        assert!(add.is_err());
+        let rename = change_project(&mut config, "x", true, |config| {
+            rename_project(config, "web", "x".into());
+        });
+        assert!(rename.is_err());
+        let reserved = change_project(&mut config, "edit", true, |config| {
+            config.paths.insert("edit".into(), project(&[]));
+        });
+        assert!(reserved.is_err());
+        assert_eq!(config.paths, before);
+    }
+
+    #[test]
+    fn conflicting_change_without_strict() {
+        let mut config = Projects::new();
+        config.paths.insert("api".into(), project(&["x"]));
+        // only a warning, like on start
+        change_project(&mut config, "x", false, |config| {
+            config.paths.insert("x".into(), project(&[]));
+        })
+        .unwrap();
+        assert!(config.paths.contains_key("x"));
+    }
+
+    #[test]
+    fn change_ignores_conflicts_of_other_projects() {
+        let mut config = Projects::new();
+        // edited into the config by hand
+        config.paths.insert("api".into(), project(&["x"]));
+        config.paths.insert("web".into(), project(&["x"]));
+        change_project(&mut config, "cli", true, |config| {
+            config.paths.insert("cli".into(), project(&[]));
+        })
+        .unwrap();
+        assert!(config.paths.contains_key("cli"));
+    }
+
+    #[test]
+    fn split_cmd_with_arguments() {
+        assert_eq!(
+            split_cmd("code --wait", "editor").unwrap(),
+            Some(("code".to_string(), vec!["--wait".to_string()]))
+        );
+    }
+
+    #[test]
+    fn split_cmd_without_arguments() {
+        assert_eq!(
+            split_cmd("vim", "editor").unwrap(),
+            Some(("vim".to_string(), vec![]))
+        );
+        assert_eq!(split_cmd("", "editor").unwrap(), None);
+    }
+
+    #[test]
+    fn split_windows_keeps_backslashes() {
+        assert_eq!(
+            split_windows(r#""C:\Program Files\Microsoft VS Code\bin\code.exe" --wait"#),
+            Some(vec![
+                r"C:\Program Files\Microsoft VS Code\bin\code.exe".to_string(),
+                "--wait".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn split_windows_empty() {
+        assert_eq!(split_windows(""), Some(vec![]));
+        assert_eq!(
+            split_windows(r#"code """#),
+            Some(vec!["code".to_string(), String::new()])
+        );
+    }
+
+    #[test]
+    fn split_windows_unclosed_quote() {
+        assert_eq!(split_windows(r#""C:\Program Files\code.exe --wait"#), None);
+    }
+
+    #[test]
+    fn since_leaves_out_never_opened_and_combines_with_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        history::init(&dir.path().join("wspick.toml"));
+        let mut config = Projects::new();
+        for (name, tags) in [("api", &["work"][..]), ("web", &["work"]), ("blog", &[])] {
+            let mut project = Project::from(format!("/srv/{name}"));
+            project.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            config.paths.insert(name.into(), project);
+        }
+        history::record("/srv/api").unwrap();
+        history::record("/srv/blog").unwrap();
+        let projects = |config: &mut Projects, args: &[&str]| {
+            let flags = Flags::parse_from(["wspick"].iter().chain(args));
+            let (entries, _) = menu_entries(config, &Menu::new(config, &flags.menu)).unwrap();
+            entries
+                .into_iter()
+                .filter(|name| !MENU_ACTIONS.contains(&name.as_str()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(projects(&mut config, &["--since", "1h"]), ["api", "blog"]);
+        assert_eq!(
+            projects(&mut config, &["--since", "1h", "--tag", "work"]),
+            ["api"]
+        );
+    }
+
+    /// `config` with `resolve_git_root` set to `resolve`
+    fn git_root_config(resolve: Option<bool>) -> Projects {
+        let mut config = Projects::new();
+        config.resolve_git_root = resolve;
+        config
+    }
+
+    #[test]
+    fn open_target_resolves_nested_dir_to_repo_root() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["repo/.git", "repo/src/nested"]);
+        let config = git_root_config(Some(true));
+        assert_eq!(
+            open_target(&config, &path(root, "repo/src/nested")),
+            path(root, "repo")
+        );
+        assert_eq!(
+            open_target(&config, &path(root, "repo")),
+            path(root, "repo")
+        );
+    }
+
+    #[test]
+    fn open_target_git_file_is_root() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["worktree/src"]);
+        fs::write(root.join("worktree/.git"), "gitdir: /elsewhere\n").unwrap();
+        let config = git_root_config(Some(true));
+        assert_eq!(
+            open_target(&config, &path(root, "worktree/src")),
+            path(root, "worktree")
+        );
+    }
+
+    #[test]
+    fn open_target_without_repo_keeps_path() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["plain/src"]);
+        let config = git_root_config(Some(true));
+        assert_eq!(
+            open_target(&config, &path(root, "plain/src")),
+            path(root, "plain/src")
+        );
+    }
+
+    #[test]
+    fn open_target_disabled_keeps_path() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["repo/.git", "repo/src"]);
+        for resolve in [Some(false), None] {
+            let config = git_root_config(resolve);
+            assert_eq!(
+                open_target(&config, &path(root, "repo/src")),
+                path(root, "repo/src")
+            );
+        }
+    }
 }
@@ -0,0 +1,185 @@
+use std::{
+    io::{stderr, Write},
+    path::PathBuf,
+    sync::atomic::Ordering,
+};
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+};
+
+use crate::{
+    change_project, edit_path, new_project, rename_project, save_config, NameValidator, Projects,
+    STRICT,
+};
+
+const HELP: &str = "d delete  r rename  e edit path  n new  / filter  q quit";
+
+/// switches the terminal to a full screen list and restores it when dropped
+struct Screen;
+impl Screen {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stderr(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen)
+    }
+}
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(stderr(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// full screen list of the configured projects with keybindings to change them
+pub fn manage(config: &mut Projects, config_file: &PathBuf, save: bool) -> Result<()> {
+    let mut selected = 0;
+    let mut filter = String::new();
+    let mut filtering = false;
+    let mut message = String::new();
+    let mut screen = Some(Screen::enter()?);
+    loop {
+        let names = config
+            .paths
+            .keys()
+            .filter(|name| name.to_lowercase().contains(&filter.to_lowercase()))
+            .cloned()
+            .collect::<Vec<_>>();
+        selected = selected.min(names.len().saturating_sub(1));
+        draw(config, &names, selected, &filter, filtering, &message)?;
+        let Event::Key(KeyEvent { code, kind, .. }) = event::read()? else {
+            continue;
+        };
+        if kind == KeyEventKind::Release {
+            continue;
+        }
+        message.clear();
+        if filtering {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => filtering = false,
+                KeyCode::Backspace => {
+                    filter.pop();
+                }
+                KeyCode::Char(c) => filter.push(c),
+                _ => (),
+            }
+            continue;
+        }
+        let current = names.get(selected).cloned();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => selected += 1,
+            KeyCode::Char('/') => filtering = true,
+            KeyCode::Char('n') => {
+                // prompts need the normal terminal
+                drop(screen.take());
+                let result = new_project(config, config_file, None, None, save);
+                screen = Some(Screen::enter()?);
+                message = match result {
+                    Ok((name, _)) => format!("added '{name}'"),
+                    Err(err) => format!("{err}"),
+                };
+            }
+            KeyCode::Char(key @ ('d' | 'r' | 'e')) => {
+                let Some(name) = current else {
+                    continue;
+                };
+                drop(screen.take());
+                let result = match key {
+                    'd' => delete(config, config_file, &name, save),
+                    'r' => rename(config, config_file, &name, save),
+                    _ => edit_path(config, config_file, &name, save)
+                        .map(|_| format!("changed '{name}'")),
+                };
+                screen = Some(Screen::enter()?);
+                message = result.unwrap_or_else(|err| format!("{err}"));
+            }
+            _ => (),
+        }
+    }
+}
+
+fn draw(
+    config: &Projects,
+    names: &[String],
+    selected: usize,
+    filter: &str,
+    filtering: bool,
+    message: &str,
+) -> Result<()> {
+    let mut out = stderr();
+    let (_, rows) = terminal::size()?;
+    // header, filter and message lines
+    let height = (rows as usize).saturating_sub(4).max(1);
+    let offset = selected.saturating_sub(height - 1);
+    queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+    queue!(out, Print(HELP), Print("\r\n"))?;
+    let cursor = if filtering { "_" } else { "" };
+    queue!(out, Print(format!("filter: {filter}{cursor}\r\n")))?;
+    for (i, name) in names.iter().enumerate().skip(offset).take(height) {
+        let line = format!("{name}  {}", config.paths[name].path);
+        if i == selected {
+            queue!(out, SetAttribute(Attribute::Reverse), Print(line))?;
+            queue!(out, SetAttribute(Attribute::Reset), Print("\r\n"))?;
+        } else {
+            queue!(out, Print(line), Print("\r\n"))?;
+        }
+    }
+    if names.is_empty() {
+        queue!(out, Print("no projects\r\n"))?;
+    }
+    queue!(
+        out,
+        cursor::MoveTo(0, rows.saturating_sub(1)),
+        Print(message)
+    )?;
+    out.flush()?;
+    Ok(())
+}
+
+pub fn delete(
+    config: &mut Projects,
+    config_file: &PathBuf,
+    name: &str,
+    save: bool,
+) -> Result<String> {
+    if !inquire::Confirm::new(&format!("delete '{name}'?"))
+        .with_default(false)
+        .prompt()?
+    {
+        return Ok(String::new());
+    }
+    config.paths.shift_remove(name);
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok(format!("deleted '{name}'"))
+}
+
+pub fn rename(
+    config: &mut Projects,
+    config_file: &PathBuf,
+    name: &str,
+    save: bool,
+) -> Result<String> {
+    let new_name = inquire::Text::new(&format!("new name for '{name}':"))
+        .with_initial_value(name)
+        .with_validator(NameValidator::new(config, name))
+        .prompt()?;
+    change_project(
+        config,
+        &new_name,
+        STRICT.load(Ordering::Relaxed),
+        |config| rename_project(config, name, new_name.clone()),
+    )?;
+    if save {
+        save_config(config, config_file)?;
+    }
+    Ok(format!("renamed '{name}' to '{new_name}'"))
+}
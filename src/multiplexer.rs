@@ -0,0 +1,82 @@
+use std::process::{Command, Stdio};
+
+use anyhow::Result;
+
+use crate::{
+    dry_run,
+    error::{CommandFailed, ErrorCode},
+    not_found_as, program,
+};
+
+/// attach to the session of the project or create it in `dir`, returns `false` for unknown
+/// multiplexers so open_cmd is used instead
+pub fn open(multiplexer: &str, name: &str, dir: &str) -> Result<bool> {
+    let session = session_name(name);
+    let inside = |var| std::env::var_os(var).is_some_and(|v| !v.is_empty());
+    // the commands to run in order and whether their failure matters
+    let commands = match multiplexer {
+        "tmux" if inside("TMUX") => vec![
+            // fails if the session already exists, then it is only switched to
+            (
+                command("tmux", &["new-session", "-d", "-s", &session, "-c", dir]),
+                false,
+            ),
+            (
+                command("tmux", &["switch-client", "-t", &format!("={session}")]),
+                true,
+            ),
+        ],
+        "tmux" => vec![(
+            command("tmux", &["new-session", "-A", "-s", &session, "-c", dir]),
+            true,
+        )],
+        // zellij can't switch sessions from the inside, the project gets a new tab instead
+        "zellij" if inside("ZELLIJ") => vec![(
+            command(
+                "zellij",
+                &["action", "new-tab", "--name", &session, "--cwd", dir],
+            ),
+            true,
+        )],
+        "zellij" => {
+            // new sessions start in the working directory
+            let mut attach = command("zellij", &["attach", "--create", &session]);
+            attach.current_dir(dir);
+            vec![(attach, true)]
+        }
+        _ => {
+            eprintln!("unknown multiplexer '{multiplexer}', use tmux or zellij. Using open_cmd");
+            return Ok(false);
+        }
+    };
+    for (mut command, required) in commands {
+        if dry_run(&command) {
+            continue;
+        }
+        if !required {
+            command.stderr(Stdio::null());
+        }
+        let status = command
+            .status()
+            .map_err(|e| not_found_as(e, ErrorCode::CmdNotFound, multiplexer))?;
+        if required && !status.success() {
+            return Err(CommandFailed {
+                cmd: multiplexer.to_string(),
+                status,
+            }
+            .into());
+        }
+    }
+    Ok(true)
+}
+
+fn command(program: &str, args: &[&str]) -> Command {
+    let mut command = Command::new(program::resolve(program));
+    command.args(args);
+    command
+}
+
+/// tmux doesn't allow `.` and `:` in session names
+fn session_name(name: &str) -> String {
+    name.replace(['.', ':'], "_")
+}
@@ -0,0 +1,94 @@
+use std::cmp::Ordering;
+
+/// compare names with embedded numbers by their value, so `proj2` comes before `proj10`.
+/// Names that only differ in case or leading zeros are ordered byte-wise, so the order is the
+/// same on every start
+pub fn compare(a: &str, b: &str, ignore_case: bool) -> Ordering {
+    let (mut a_rest, mut b_rest) = (a, b);
+    loop {
+        let ordering = match (a_rest.chars().next(), b_rest.chars().next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, x_rest) = split_number(a_rest);
+                let (y, y_rest) = split_number(b_rest);
+                (a_rest, b_rest) = (x_rest, y_rest);
+                compare_numbers(x, y)
+            }
+            (Some(x), Some(y)) => {
+                a_rest = &a_rest[x.len_utf8()..];
+                b_rest = &b_rest[y.len_utf8()..];
+                match ignore_case {
+                    true => x.to_lowercase().cmp(y.to_lowercase()),
+                    false => x.cmp(&y),
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// the leading digits of `text` and the rest
+fn split_number(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+/// compare numbers of any length without parsing them
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(names: &[&str], ignore_case: bool) -> Vec<String> {
+        let mut names = names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        names.sort_by(|a, b| compare(a, b, ignore_case));
+        names
+    }
+
+    #[test]
+    fn numbers_by_value() {
+        assert_eq!(
+            sorted(&["proj10", "proj2", "proj1", "proj100", "proj20"], true),
+            ["proj1", "proj2", "proj10", "proj20", "proj100"]
+        );
+        assert_eq!(
+            sorted(&["v1.10", "v1.9", "v10.0", "v1.2"], true),
+            ["v1.2", "v1.9", "v1.10", "v10.0"]
+        );
+    }
+
+    #[test]
+    fn case_ignored() {
+        assert_eq!(
+            sorted(&["cli", "Blog", "api", "Proj10", "proj9"], true),
+            ["api", "Blog", "cli", "proj9", "Proj10"]
+        );
+    }
+
+    #[test]
+    fn case_sensitive() {
+        assert_eq!(
+            sorted(&["cli", "Blog", "api", "Proj10", "proj9"], false),
+            ["Blog", "Proj10", "api", "cli", "proj9"]
+        );
+    }
+
+    #[test]
+    fn equal_ignoring_case_uppercase_first() {
+        // the order doesn't depend on the input order
+        assert_eq!(sorted(&["foo", "Foo", "FOO"], true), ["FOO", "Foo", "foo"]);
+        assert_eq!(sorted(&["Foo", "FOO", "foo"], true), ["FOO", "Foo", "foo"]);
+        assert_eq!(sorted(&["proj2", "proj02"], true), ["proj02", "proj2"]);
+    }
+}
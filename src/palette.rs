@@ -0,0 +1,192 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{
+    add_dir, back_on_esc, edit_path, edit_project, manage, new_project, open_tag, page_size,
+    remove_dirs, select_project, Back, Menu, Projects,
+};
+
+/// actions offered in the palette
+#[derive(Clone, Copy)]
+enum Action {
+    OpenProject,
+    NewProject,
+    EditPath,
+    ManageProjects,
+    DeleteProject,
+    RenameProject,
+    OpenTag,
+    NewDir,
+    RemoveDirs,
+    EditConfig,
+}
+
+impl Action {
+    const ALL: [Action; 10] = [
+        Action::OpenProject,
+        Action::NewProject,
+        Action::EditPath,
+        Action::ManageProjects,
+        Action::DeleteProject,
+        Action::RenameProject,
+        Action::OpenTag,
+        Action::NewDir,
+        Action::RemoveDirs,
+        Action::EditConfig,
+    ];
+
+    /// name used with `wspick action <name>`
+    fn name(self) -> &'static str {
+        match self {
+            Action::OpenProject => "open",
+            Action::NewProject => "new",
+            Action::EditPath => "edit-path",
+            Action::ManageProjects => "manage",
+            Action::DeleteProject => "delete",
+            Action::RenameProject => "rename",
+            Action::OpenTag => "open-tag",
+            Action::NewDir => "new-dir",
+            Action::RemoveDirs => "manage-dirs",
+            Action::EditConfig => "edit",
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Action::OpenProject => "open project",
+            Action::NewProject => "new project",
+            Action::EditPath => "change path of a project",
+            Action::ManageProjects => "manage projects",
+            Action::DeleteProject => "delete project",
+            Action::RenameProject => "rename project",
+            Action::OpenTag => "open all projects with a tag",
+            Action::NewDir => "add directory to search",
+            Action::RemoveDirs => "remove searched directories",
+            Action::EditConfig => "edit config",
+        })
+    }
+}
+
+/// searchable list of all actions, returns the project to open if the action selected one
+pub fn palette(
+    config: &mut Projects,
+    config_file: &PathBuf,
+    menu: Menu,
+    log_dir: &Path,
+    save: bool,
+) -> Result<Option<(String, String)>> {
+    let theme = config.theme.clone().unwrap_or_default();
+    let mut select = inquire::Select::new("action:", Action::ALL.to_vec())
+        .with_page_size(page_size())
+        .with_render_config(theme.render_config());
+    if !theme.show_help() {
+        select = select.without_help_message();
+    }
+    // Esc in an action goes back to the palette, Esc in the palette exits
+    loop {
+        let Some(action) = select.clone().prompt_skippable()? else {
+            return Ok(None);
+        };
+        let result = run(action, config, config_file, menu.clone(), log_dir, save);
+        match back_on_esc(config, result)? {
+            // the project menu was closed
+            Back::Done(None) if matches!(action, Action::OpenProject) => {
+                if config.esc_exits.unwrap_or(false) {
+                    return Ok(None);
+                }
+            }
+            Back::Done(project) => return Ok(project),
+            Back::Menu => (),
+            Back::Exit => return Ok(None),
+        }
+    }
+}
+
+/// run a single action by its name without the palette, for `wspick action <name>`
+pub fn action(
+    name: Option<&str>,
+    config: &mut Projects,
+    config_file: &PathBuf,
+    menu: Menu,
+    log_dir: &Path,
+    save: bool,
+) -> Result<Option<(String, String)>> {
+    let names = Action::ALL.map(Action::name).join(", ");
+    let name = name.ok_or_else(|| anyhow::anyhow!("missing action, use one of {names}"))?;
+    let action = Action::ALL
+        .into_iter()
+        .find(|a| a.name() == name)
+        .ok_or_else(|| anyhow::anyhow!("unknown action '{name}', use one of {names}"))?;
+    // the action is the first menu, Esc exits
+    let result = run(action, config, config_file, menu, log_dir, save);
+    match back_on_esc(config, result)? {
+        Back::Done(project) => Ok(project),
+        Back::Menu | Back::Exit => Ok(None),
+    }
+}
+
+/// returns the project to open if the action selected one
+fn run(
+    action: Action,
+    config: &mut Projects,
+    config_file: &PathBuf,
+    menu: Menu,
+    log_dir: &Path,
+    save: bool,
+) -> Result<Option<(String, String)>> {
+    match action {
+        Action::OpenProject => return select_project(config, config_file, None, menu, save),
+        Action::NewProject => return new_project(config, config_file, None, None, save).map(Some),
+        Action::EditPath => {
+            if let Some(name) = select_configured(config)? {
+                edit_path(config, config_file, &name, save)?;
+            }
+        }
+        Action::ManageProjects => manage::manage(config, config_file, save)?,
+        Action::DeleteProject => {
+            if let Some(name) = select_configured(config)? {
+                println!("{}", manage::delete(config, config_file, &name, save)?);
+            }
+        }
+        Action::RenameProject => {
+            if let Some(name) = select_configured(config)? {
+                println!("{}", manage::rename(config, config_file, &name, save)?);
+            }
+        }
+        Action::OpenTag => {
+            let mut tags = config
+                .paths
+                .values()
+                .flat_map(|p| p.tags.iter().cloned())
+                .collect::<Vec<_>>();
+            tags.sort();
+            tags.dedup();
+            if tags.is_empty() {
+                println!("no project has tags");
+            } else {
+                let tag = inquire::Select::new("tag:", tags).prompt()?;
+                open_tag(config, &tag, false, log_dir)?;
+            }
+        }
+        Action::NewDir => add_dir(config, config_file, save)?,
+        Action::RemoveDirs => remove_dirs(config, config_file, save)?,
+        Action::EditConfig => edit_project(config, config_file)?,
+    }
+    Ok(None)
+}
+
+/// ask for one of the projects in `paths`, `None` if there are none
+fn select_configured(config: &Projects) -> Result<Option<String>> {
+    if config.paths.is_empty() {
+        println!("no projects configured");
+        return Ok(None);
+    }
+    let names = config.paths.keys().cloned().collect();
+    Ok(Some(inquire::Select::new("project:", names).prompt()?))
+}
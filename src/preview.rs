@@ -0,0 +1,50 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::expand::expand_path;
+
+/// lines of the readme that are shown
+const PREVIEW_LINES: usize = 30;
+
+/// the readme of a project, `README.md` is preferred over other `README*` files
+fn find_readme(dir: &Path) -> Option<PathBuf> {
+    let mut readmes = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.to_lowercase().starts_with("readme") && path.is_file()
+        })
+        .collect::<Vec<_>>();
+    readmes.sort_by_key(|path| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        (name.to_lowercase() != "readme.md", name.to_string())
+    });
+    readmes.into_iter().next()
+}
+
+/// print the start of the readme of the project to stderr, next to the menu
+pub fn print_readme(name: &str, path: &str) -> Result<()> {
+    let Some(readme) = find_readme(&expand_path(path)) else {
+        eprintln!("'{name}' has no readme");
+        return Ok(());
+    };
+    eprintln!("---- {} ----", readme.display());
+    let mut lines = BufReader::new(fs::File::open(&readme)?).lines();
+    for line in lines.by_ref().take(PREVIEW_LINES) {
+        // binary files are shown as far as they are text
+        let Ok(line) = line else { break };
+        eprintln!("{line}");
+    }
+    if lines.next().is_some() {
+        eprintln!("...");
+    }
+    eprintln!("----");
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+/// programs already looked up in `PATH` during this run
+fn cache() -> &'static Mutex<HashMap<String, PathBuf>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, PathBuf>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// absolute path of a program from `PATH`, looked up once per run. Paths and programs that
+/// can't be found are returned unchanged so starting them reports the usual error
+pub fn resolve(program: &str) -> PathBuf {
+    let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(path) = cache.get(program) {
+        return path.clone();
+    }
+    let Some(path) = search_path(program) else {
+        return PathBuf::from(program);
+    };
+    cache.insert(program.to_string(), path.clone());
+    path
+}
+
+/// forget all looked up programs, e.g. after the config was edited
+pub fn clear_cache() {
+    cache().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+#[cfg(unix)]
+fn search_path(program: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if program.contains('/') {
+        return None;
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| {
+            path.metadata()
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        })
+}
+
+/// windows also tries `PATHEXT` extensions, which is left to `Command`
+#[cfg(not(unix))]
+fn search_path(_program: &str) -> Option<PathBuf> {
+    None
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use ignore::WalkBuilder;
+
+/// directory levels searched below the project
+const MAX_DEPTH: usize = 8;
+/// files compared at most, so large projects open without a noticeable delay
+const MAX_FILES: usize = 5000;
+
+/// most recently modified file in the directory, skipping hidden and git ignored files.
+/// `None` if it contains no files
+pub fn most_recent(dir: &str) -> Option<PathBuf> {
+    WalkBuilder::new(dir)
+        .max_depth(Some(MAX_DEPTH))
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .take(MAX_FILES)
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.into_path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
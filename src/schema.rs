@@ -0,0 +1,33 @@
+use anyhow::Result;
+use schemars::{json_schema, Schema, SchemaGenerator};
+
+use crate::Projects;
+
+/// JSON Schema of the config file for validation and completion in editors
+pub fn schema() -> Result<String> {
+    let schema = schemars::schema_for!(Projects);
+    Ok(serde_json::to_string_pretty(&schema)?)
+}
+
+/// entries that can be given as a plain path instead of the table
+pub fn or_path(schema: &mut Schema) {
+    let table = schema.clone();
+    *schema = json_schema!({
+        "anyOf": [{ "type": "string" }, table]
+    });
+}
+
+/// projects can also be given as a list of paths instead of the table
+pub fn or_paths(schema: &mut Schema) {
+    let table = schema.clone();
+    *schema = json_schema!({
+        "anyOf": [{ "type": "string" }, { "type": "array", "items": { "type": "string" } }, table]
+    });
+}
+
+/// the path of a project table, more folders are given as a list
+pub fn paths(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [{ "type": "string" }, { "type": "array", "items": { "type": "string" }, "minItems": 1 }]
+    })
+}
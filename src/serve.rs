@@ -0,0 +1,114 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::atomic::Ordering,
+};
+
+use anyhow::Result;
+
+use crate::{
+    check_names, fill_defaults, load_config, menu_order, program, resolve, scan_sources,
+    select_project, validate_theme, Menu, MenuFlags, Projects, STRICT,
+};
+
+/// listen on a unix socket and answer selection requests until killed
+///
+/// every request is a single line, answers are written back on the same connection:
+/// - `list`: one `name\tpath` line per project followed by an empty line
+/// - `resolve <name>`: `ok\t<path>` or `err\t<message>`
+/// - `pick`: show the menu in the terminal of the server, answers like `resolve`
+/// - `reload`: read the config again and rescan all dirs, answers `ok`
+pub fn serve(
+    config: &mut Projects,
+    config_file: &PathBuf,
+    socket: &Path,
+    menu: &MenuFlags,
+    save: bool,
+) -> Result<()> {
+    if socket.try_exists()? {
+        if UnixStream::connect(socket).is_ok() {
+            anyhow::bail!(
+                "another server is already listening on '{}'",
+                socket.display()
+            );
+        }
+        // left over from a server that did not shut down cleanly
+        fs::remove_file(socket)?;
+    }
+    if let Some(parent) = socket.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(socket)?;
+    eprintln!("listening on '{}'", socket.display());
+    let mut found = scan_sources(config)?;
+    for stream in listener.incoming() {
+        let result = stream
+            .map_err(anyhow::Error::from)
+            .and_then(|stream| handle(stream, config, config_file, &mut found, menu, save));
+        if let Err(err) = result {
+            eprintln!("request failed: {err:#}");
+        }
+    }
+    Ok(())
+}
+
+fn handle(
+    stream: UnixStream,
+    config: &mut Projects,
+    config_file: &PathBuf,
+    found: &mut Vec<(String, String)>,
+    menu: &MenuFlags,
+    save: bool,
+) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut writer = &stream;
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let request = line.trim_end_matches(['\n', '\r']);
+        match respond(request, config, config_file, found, menu, save) {
+            Ok(response) => writeln!(writer, "{response}")?,
+            Err(err) => writeln!(writer, "err\t{}", format!("{err:#}").replace('\n', " "))?,
+        }
+        line.clear();
+    }
+    Ok(())
+}
+
+fn respond(
+    request: &str,
+    config: &mut Projects,
+    config_file: &PathBuf,
+    found: &mut Vec<(String, String)>,
+    menu: &MenuFlags,
+    save: bool,
+) -> Result<String> {
+    let (cmd, arg) = request.split_once(' ').unwrap_or((request, ""));
+    Ok(match cmd {
+        "list" => menu_order(config, found)
+            .iter()
+            .map(|(name, path)| format!("{name}\t{path}\n"))
+            .collect(),
+        "resolve" => format!("ok\t{}", resolve(config, Some(found), arg)?.1),
+        "pick" => match select_project(config, config_file, None, Menu::new(config, menu), save)? {
+            Some((_, path)) => format!("ok\t{path}"),
+            None => anyhow::bail!("canceled"),
+        },
+        "reload" => {
+            if config_file.try_exists()? {
+                let mut reloaded = load_config(config_file)?;
+                // like on start, with --strict ambiguous names keep the previous config
+                fill_defaults(&mut reloaded);
+                check_names(&reloaded, None, STRICT.load(Ordering::Relaxed))?;
+                validate_theme(&mut reloaded);
+                *config = reloaded;
+            }
+            // the edited config may use other programs or PATH may have changed
+            program::clear_cache();
+            *found = scan_sources(config)?;
+            String::from("ok")
+        }
+        _ => anyhow::bail!("unknown request '{request}'"),
+    })
+}
@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::Flags;
+
+/// shell function `wscd` that changes into the directory selected with `wspick cd`
+pub fn init_script(shell: &str) -> Result<&'static str> {
+    Ok(match shell {
+        "bash" | "zsh" | "sh" => {
+            r#"wscd() {
+    local dir
+    dir="$(command wspick cd "$@")" && [ -n "$dir" ] && cd "$dir"
+}
+"#
+        }
+        "fish" => {
+            r#"function wscd
+    set -l dir (command wspick cd $argv)
+    and test -n "$dir"
+    and cd $dir
+end
+"#
+        }
+        "powershell" | "pwsh" => {
+            r#"function wscd {
+    $dir = wspick cd @args
+    if ($LASTEXITCODE -eq 0 -and $dir) { Set-Location $dir }
+}
+"#
+        }
+        _ => anyhow::bail!("unsupported shell '{shell}', use bash, zsh, fish or powershell"),
+    })
+}
+
+/// completion script for the options and flags of wspick
+pub fn completions(shell: &str) -> Result<String> {
+    let shell = Shell::from_str(shell).map_err(|_| {
+        anyhow::anyhow!("unsupported shell '{shell}', use bash, zsh, fish, elvish or powershell")
+    })?;
+    let mut script = vec![];
+    clap_complete::generate(shell, &mut Flags::command(), "wspick", &mut script);
+    let script = String::from_utf8(script)?;
+    // the first argument also completes commands and project names from `wspick --list-names`
+    Ok(match shell {
+        Shell::Bash => script + BASH_NAMES,
+        Shell::Zsh => {
+            let (start, end) = script
+                .rsplit_once("if [ \"$funcstack[1]\" = \"_wspick\" ]")
+                .ok_or_else(|| anyhow::anyhow!("unexpected zsh completion script"))?;
+            // the spec of the first positional argument ends with its completion function
+            let arg = start
+                .find("'::cmd_or_path")
+                .ok_or_else(|| anyhow::anyhow!("unexpected zsh completion script"))?;
+            let (head, tail) = start.split_at(arg);
+            let tail = tail.replacen(":_default'", ":_wspick_names'", 1);
+            format!("{head}{tail}{ZSH_NAMES}if [ \"$funcstack[1]\" = \"_wspick\" ]{end}")
+        }
+        Shell::Fish => script + FISH_NAMES,
+        _ => script,
+    })
+}
+
+const BASH_NAMES: &str = r#"
+_wspick_names() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" word
+    for word in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        [[ $word != -* ]] && { _wspick "$@"; return; }
+    done
+    if [[ $cur != -* ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(command wspick --list-names 2>/dev/null)" -- "$cur"))
+        [[ ${#COMPREPLY[@]} -gt 0 ]] && return 0
+    fi
+    _wspick "$@"
+}
+complete -F _wspick_names -o bashdefault -o default wspick
+"#;
+
+const ZSH_NAMES: &str = r#"(( $+functions[_wspick_names] )) ||
+_wspick_names() {
+    local -a names
+    names=("${(@f)$(command wspick --list-names 2>/dev/null)}")
+    compadd -a names
+    _files
+}
+
+"#;
+
+const FISH_NAMES: &str = r#"complete -c wspick -n '__fish_is_first_arg' -a '(command wspick --list-names 2>/dev/null)'
+"#;
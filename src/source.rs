@@ -0,0 +1,393 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::Ordering,
+};
+
+use anyhow::Result;
+
+use crate::{
+    dry_run,
+    error::ErrorCode,
+    expand::{expand, expand_path},
+    not_found_as, Projects, SearchDir, VERBOSE,
+};
+
+/// provides projects that are shown in the menu in addition to the configured paths
+pub trait ProjectSource: Send + Sync {
+    /// list the projects as name and path
+    fn projects(&self, config: &Projects) -> Result<Vec<(String, String)>>;
+    /// the configured dir or command, for messages
+    fn name(&self) -> &str;
+}
+
+/// all sources configured in `dirs` and `source_cmds`, in that order. They own their settings,
+/// so each can be scanned on its own thread
+pub fn sources(config: &Projects) -> Vec<Box<dyn ProjectSource>> {
+    let dirs = config.dirs.iter().flatten().cloned();
+    let cmds = config.source_cmds.iter().flatten().cloned();
+    dirs.map(|dir| Box::new(DirSource(dir)) as Box<dyn ProjectSource>)
+        .chain(cmds.map(|cmd| Box::new(CommandSource(cmd)) as Box<dyn ProjectSource>))
+        .collect()
+}
+
+/// the directories at the configured depth of a search directory
+pub struct DirSource(pub SearchDir);
+
+impl ProjectSource for DirSource {
+    fn projects(&self, config: &Projects) -> Result<Vec<(String, String)>> {
+        let SearchDir { path, depth, .. } = &self.0;
+        let path = &expand(path);
+        if *depth == 0 {
+            // the directory itself is the project
+            fs::metadata(path).map_err(|e| not_found_as(e, ErrorCode::PathMissing, path))?;
+            let Some(name) = Path::new(path).file_name() else {
+                skipped(Path::new(path), "it has no name");
+                return Ok(vec![]);
+            };
+            return Ok(vec![(name.to_string_lossy().to_string(), path.clone())]);
+        }
+        let mut found = subdirs(config, path)?;
+        for _ in 1..*depth {
+            // unreadable directories below the search directory are skipped
+            found = found
+                .into_iter()
+                .flat_map(|(name, dir)| {
+                    // a repository is the project, not its `src` or `target`
+                    if Path::new(&dir).join(".git").exists() {
+                        return vec![(name, dir)];
+                    }
+                    subdirs(config, &dir).unwrap_or_else(|err| {
+                        skipped(Path::new(&dir), &format!("{err:#}"));
+                        vec![]
+                    })
+                })
+                .collect();
+        }
+        Ok(found)
+    }
+
+    fn name(&self) -> &str {
+        &self.0.path
+    }
+}
+
+/// all subdirectories of `dir` that can be shown as projects
+fn subdirs(config: &Projects, dir: &str) -> Result<Vec<(String, String)>> {
+    let mut found = vec![];
+    let dir_path = PathBuf::from(dir);
+    match dir_path.file_name().map(|d| d.to_str()) {
+        None => {
+            skipped(&dir_path, "it has no name");
+            return Ok(found);
+        }
+        Some(None) => {
+            skipped(&dir_path, "name is not valid UTF-8");
+            return Ok(found);
+        }
+        Some(Some(_)) => (),
+    }
+    // filter for directories
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir).map_err(|e| not_found_as(e, ErrorCode::PathMissing, dir))? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                skipped(&dir_path, &format!("an entry can't be read: {err}"));
+                continue;
+            }
+        };
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() => paths.push(entry.path()),
+            Ok(_) => skipped(&entry.path(), "not a directory"),
+            Err(err) => skipped(&entry.path(), &format!("type can't be read: {err}")),
+        }
+    }
+    if let Some(true) = config.exclude_proj_dirs {
+        // filter out directories that contain or are projects or other searched dirs
+        let configured = config
+            .paths
+            .values()
+            .map(|p| &p.path)
+            .chain(config.dirs.iter().flatten().map(|d| &d.path))
+            .map(|path| canonical(expand_path(path)))
+            .collect::<Vec<_>>();
+        paths.retain(|p| {
+            let path = canonical(p);
+            let excluded = configured.iter().any(|c| c.starts_with(&path));
+            if excluded {
+                skipped(
+                    p,
+                    "contains a configured project or dir (exclude_proj_dirs)",
+                );
+            }
+            !excluded
+        });
+    }
+    for path in paths {
+        let Some(file_name) = path.file_name() else {
+            skipped(&path, "it has no name");
+            continue;
+        };
+        if file_name.to_string_lossy().starts_with('.') {
+            skipped(&path, "hidden");
+            continue;
+        }
+        // paths are stored as strings in the config and passed on as such
+        let (Some(path_str), Some(name)) = (path.to_str(), file_name.to_str()) else {
+            eprintln!("skipping {path:?}: name is not valid UTF-8");
+            continue;
+        };
+        // names with newlines and the like would break line based output
+        if path_str.chars().any(char::is_control) {
+            eprintln!("skipping {path:?}: name contains control characters");
+            continue;
+        }
+        found.push((name.into(), path_str.into()));
+    }
+    // the order of read_dir depends on the file system
+    found.sort();
+    Ok(found)
+}
+
+/// report a skipped entry with `--verbose`, silent otherwise
+fn skipped(path: &Path, reason: &str) {
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!("skipped {path:?}: {reason}");
+    }
+}
+
+/// canonical form of a path for comparisons, the path itself if it doesn't exist
+fn canonical(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// shell command that prints one `name\tpath` line per project
+pub struct CommandSource(pub String);
+
+impl ProjectSource for CommandSource {
+    fn projects(&self, _config: &Projects) -> Result<Vec<(String, String)>> {
+        let cmd = &self.0;
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        command.arg(cmd);
+        if dry_run(&command) {
+            return Ok(vec![]);
+        }
+        let output = command.output()?;
+        if !output.status.success() {
+            anyhow::bail!("source command '{cmd}' failed ({})", output.status);
+        }
+        let mut found = vec![];
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            match line.split_once('\t') {
+                Some((name, path)) if !name.is_empty() && !path.is_empty() => {
+                    found.push((name.to_string(), path.to_string()))
+                }
+                _ if line.trim().is_empty() => (),
+                _ => eprintln!("skipping line {line:?} of '{cmd}': expected `name<TAB>path`"),
+            }
+        }
+        Ok(found)
+    }
+
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// lowercase names with `-` as separator, names that only become equal by that get a number
+pub fn normalize_names(found: Vec<(String, String)>) -> Vec<(String, String)> {
+    // the original name each normalized name was taken by
+    let mut taken: HashMap<String, String> = HashMap::new();
+    found
+        .into_iter()
+        .map(|(name, path)| {
+            let base = name.to_lowercase().replace([' ', '_'], "-");
+            let mut key = base.clone();
+            let mut n = 1;
+            while taken.get(&key).is_some_and(|original| *original != name) {
+                n += 1;
+                key = format!("{base}-{n}");
+            }
+            taken.insert(key.clone(), name);
+            (key, path)
+        })
+        .collect()
+}
+
+/// names shown for found projects, each name stands for exactly one path. Projects found again
+/// under their configured path are left out, names used more than once get the parent directory
+/// appended, e.g. `utils (app)`, or the whole path if that isn't enough
+pub fn disambiguate(
+    config: &Projects,
+    groups: Vec<Vec<(String, String)>>,
+) -> Vec<Vec<(String, String)>> {
+    let configured = |name: &str, path: &str| {
+        config
+            .paths
+            .get(name)
+            .is_some_and(|p| canonical(expand_path(&p.path)) == canonical(path))
+    };
+    let mut seen = HashSet::new();
+    let groups: Vec<Vec<_>> = groups
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                // the same directory reached from two dirs is shown once
+                .filter(|(name, path)| {
+                    seen.insert((name.clone(), path.clone())) && !configured(name, path)
+                })
+                .collect()
+        })
+        .collect();
+    let parent = |name: &str, path: &str| {
+        let parent = Path::new(path).parent().and_then(Path::file_name)?;
+        Some(format!("{name} ({})", parent.to_string_lossy()))
+    };
+    let full_path = |name: &str, path: &str| Some(format!("{name} ({path})"));
+    // the original names stay for the second try
+    let mut shown = groups.clone();
+    for rename in [parent, full_path] {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (name, _) in shown.iter().flatten() {
+            *counts.entry(name).or_default() += 1;
+        }
+        let taken = |name: &str| counts[name] > 1 || config.paths.contains_key(name);
+        let renamed = groups
+            .iter()
+            .zip(&shown)
+            .map(|(group, shown)| {
+                group
+                    .iter()
+                    .zip(shown)
+                    .map(|((name, path), (current, _))| {
+                        let current = match taken(current) {
+                            true => rename(name, path).unwrap_or_else(|| current.clone()),
+                            false => current.clone(),
+                        };
+                        (current, path.clone())
+                    })
+                    .collect()
+            })
+            .collect();
+        shown = renamed;
+    }
+    shown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_dirs, path};
+
+    fn names(found: &[(String, String)]) -> Vec<&str> {
+        found.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn exclude_proj_dirs_ignores_overlapping_names() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["code/app", "code/snapper/tool"]);
+        let mut config = Projects::new();
+        config.exclude_proj_dirs = Some(true);
+        let tool = path(root, "code/snapper/tool");
+        config.paths.insert("tool".into(), tool.into());
+        // `snapper` contains a project, `app` is only part of its name
+        let found = subdirs(&config, &path(root, "code")).unwrap();
+        assert_eq!(names(&found), ["app"]);
+    }
+
+    #[test]
+    fn exclude_proj_dirs_compares_paths_not_substrings() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["code/src", "code/web", "other/src"]);
+        let mut config = Projects::new();
+        config.exclude_proj_dirs = Some(true);
+        // a project with the same name in another tree excludes nothing
+        let other = path(root, "other/src");
+        config.paths.insert("src".into(), other.into());
+        let found = subdirs(&config, &path(root, "code")).unwrap();
+        assert_eq!(names(&found), ["src", "web"]);
+    }
+
+    #[test]
+    fn exclude_proj_dirs_excludes_ancestors_of_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["code/group/repo", "code/web"]);
+        let mut config = Projects::new();
+        config.exclude_proj_dirs = Some(true);
+        config.dirs = Some(vec![
+            SearchDir::from(path(root, "code")),
+            SearchDir::from(path(root, "code/group")),
+        ]);
+        let found = subdirs(&config, &path(root, "code")).unwrap();
+        assert_eq!(names(&found), ["web"]);
+    }
+
+    #[test]
+    fn disambiguate_same_names_by_parent() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["app/utils", "app/web", "lib/utils"]);
+        let config = Projects::new();
+        let groups = ["app", "lib"]
+            .map(|dir| DirSource(SearchDir::from(path(root, dir))).projects(&config))
+            .map(Result::unwrap)
+            .to_vec();
+        let shown = disambiguate(&config, groups);
+        assert_eq!(names(&shown[0]), ["utils (app)", "web"]);
+        assert_eq!(names(&shown[1]), ["utils (lib)"]);
+        assert_eq!(shown[1][0].1, path(root, "lib/utils"));
+    }
+
+    #[test]
+    fn depth_stops_at_git_repositories() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(
+            root,
+            &[
+                "org/api/src",
+                "org/web/src",
+                "repo/.git",
+                "repo/src",
+                "repo/target",
+            ],
+        );
+        let dir = SearchDir {
+            path: root.to_string_lossy().to_string(),
+            depth: 2,
+            sort: None,
+        };
+        let found = DirSource(dir).projects(&Projects::new()).unwrap();
+        assert_eq!(names(&found), ["api", "web", "repo"]);
+        assert_eq!(found[2].1, path(root, "repo"));
+    }
+
+    /// other systems don't allow newlines in file names
+    #[cfg(unix)]
+    #[test]
+    fn control_characters_are_skipped() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_dirs(root, &["evil\nproject", "evil", "ok"]);
+        let found = subdirs(&Projects::new(), &root.to_string_lossy()).unwrap();
+        assert_eq!(names(&found), ["evil", "ok"]);
+        assert_eq!(found[0].1, path(root, "evil"));
+    }
+}
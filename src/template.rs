@@ -0,0 +1,84 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use inquire::validator::Validation;
+
+use crate::{expand::expand_path, Projects};
+
+const EXISTING_PATH: &str = "[existing path]";
+
+/// ask whether to create the project from one of the `template_dirs`, returns the path of the
+/// created project or `None` if an existing path should be used
+pub fn create_project(config: &Projects, name: &str) -> Result<Option<String>> {
+    let templates = config.template_dirs.clone().unwrap_or_default();
+    if templates.is_empty() {
+        return Ok(None);
+    }
+    let options = [EXISTING_PATH.to_string()]
+        .into_iter()
+        .chain(templates)
+        .collect();
+    let template = inquire::Select::new("create from:", options).prompt()?;
+    if template == EXISTING_PATH {
+        return Ok(None);
+    }
+    // suggest a place next to the other projects
+    let suggestion = config
+        .dirs
+        .iter()
+        .flatten()
+        .find(|dir| dir.depth == 1)
+        .map(|dir| {
+            Path::new(&dir.path)
+                .join(name)
+                .to_string_lossy()
+                .to_string()
+        })
+        .unwrap_or_default();
+    let path = inquire::Text::new("new project path:")
+        .with_initial_value(&suggestion)
+        .with_validator(|input: &str| {
+            Ok(if input.is_empty() {
+                Validation::Invalid("path must not be empty".into())
+            } else if expand_path(input).try_exists()? {
+                Validation::Invalid("path already exists".into())
+            } else {
+                Validation::Valid
+            })
+        })
+        .prompt()?;
+    copy_dir(&expand_path(&template), &expand_path(&path))
+        .with_context(|| format!("copying template '{template}' to '{path}' failed"))?;
+    Ok(Some(path))
+}
+
+/// copy `from` recursively to the not yet existing `to`
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to.parent().unwrap_or(to))?;
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_link(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_link(from: &Path, to: &Path) -> Result<()> {
+    fs::copy(from, to)?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use std::{fs, path::Path};
+
+/// directories below `root`, created with their parents
+pub(crate) fn create_dirs(root: &Path, dirs: &[&str]) {
+    for dir in dirs {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+}
+
+/// `path` below `root` as it is written in the config
+pub(crate) fn path(root: &Path, path: &str) -> String {
+    root.join(path).to_string_lossy().to_string()
+}
@@ -0,0 +1,108 @@
+use inquire::ui::{Color, RenderConfig, StyleSheet, Styled};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+
+/// appearance of the selection menu
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct Theme {
+    /// base style to start from: "default", "minimal" or "boxed"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    /// prefix shown in front of the highlighted entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_prefix: Option<String>,
+    /// color of the highlighted entry, e.g. "cyan", "light_green" or "#ff8800"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_color: Option<String>,
+    /// show the help message below the menu
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help_message: Option<bool>,
+    /// `highlight_color` checked by `validate` when the config was loaded
+    #[serde(skip)]
+    #[schemars(skip)]
+    color: Option<Color>,
+}
+
+impl Theme {
+    /// report an unknown preset or color once after loading the config, they are ignored when
+    /// building the menu
+    pub fn validate(&mut self) {
+        if let Some(preset) = self.preset.as_deref() {
+            if !PRESETS.contains(&preset) {
+                eprintln!("unknown theme preset '{preset}', using default");
+            }
+        }
+        self.color = self.highlight_color.as_deref().and_then(|c| {
+            let color = parse_color(c);
+            if color.is_none() {
+                eprintln!("unknown theme color '{c}', using default");
+            }
+            color
+        });
+    }
+
+    /// build the render config for the menu
+    pub fn render_config(&self) -> RenderConfig<'_> {
+        let mut config = match self.preset.as_deref() {
+            Some("minimal") => {
+                RenderConfig::empty().with_highlighted_option_prefix(Styled::new(">"))
+            }
+            Some("boxed") => RenderConfig::default()
+                .with_prompt_prefix(Styled::new("[?]").with_fg(Color::LightGreen))
+                .with_highlighted_option_prefix(Styled::new("[>]").with_fg(Color::LightCyan))
+                .with_selected_option(Some(
+                    StyleSheet::new()
+                        .with_fg(Color::Black)
+                        .with_bg(Color::LightCyan),
+                )),
+            _ => RenderConfig::default(),
+        };
+        if let Some(prefix) = &self.selected_prefix {
+            config.highlighted_option_prefix = Styled::new(prefix.as_str());
+        }
+        if let Some(color) = self.color {
+            config.highlighted_option_prefix = config.highlighted_option_prefix.with_fg(color);
+            config.selected_option = Some(StyleSheet::new().with_fg(color));
+        }
+        config
+    }
+
+    /// whether to show the help message, the minimal preset hides it by default
+    pub fn show_help(&self) -> bool {
+        self.help_message
+            .unwrap_or(self.preset.as_deref() != Some("minimal"))
+    }
+}
+
+/// base styles of `preset`
+const PRESETS: [&str; 3] = ["default", "minimal", "boxed"];
+
+fn parse_color(color: &str) -> Option<Color> {
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    let color = match color.to_lowercase().replace(['-', ' '], "_").as_str() {
+        "black" => Color::Black,
+        "red" | "light_red" => Color::LightRed,
+        "dark_red" => Color::DarkRed,
+        "green" | "light_green" => Color::LightGreen,
+        "dark_green" => Color::DarkGreen,
+        "yellow" | "light_yellow" => Color::LightYellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" | "light_blue" => Color::LightBlue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" | "light_magenta" => Color::LightMagenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" | "light_cyan" => Color::LightCyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        _ => return None,
+    };
+    Some(color)
+}